@@ -1,13 +1,16 @@
 use std::ffi::OsString;
 use std::num::NonZeroU16;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::thread::sleep;
 use std::time::Duration;
 use clap::Parser;
-use crate::launcher_rewrite::{GAME_INSTANCE_COUNT, launch_game};
-use crate::launcher_rewrite::profiles::ModLoader;
+use crate::launcher_rewrite::{get_launch_command, launch_game};
+use crate::launcher_rewrite::authentication::login::login_with_device_code;
+use crate::launcher_rewrite::hooks::LaunchHooks;
+use crate::launcher_rewrite::profiles::{ModLoader, QuickPlay};
 use crate::launcher_rewrite::mod_loader_version_manifest::LATEST_STABLE_TEXT;
+use crate::launcher_rewrite::util::paths::{expand_path, DEFAULT_MC_DIRECTORY};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -24,10 +27,38 @@ struct Args {
     width: Option<u32>,
     #[arg(long)]
     height: Option<u32>,
-    #[arg(short, long, default_value_t = ("%appdata%/.minecraft/".to_owned()))]
+    #[arg(short, long, default_value_t = DEFAULT_MC_DIRECTORY.to_owned())]
     dir: String,
     #[arg(short, long, default_value_t = 2)]
     memory: u16,
+    #[arg(long)]
+    java: Option<String>,
+    #[arg(long, allow_hyphen_values = true)]
+    jvm_args: Option<String>,
+    #[arg(long, value_name = "WORLD", group = "quick_play")]
+    quick_play_singleplayer: Option<String>,
+    #[arg(long, value_name = "HOST:PORT", group = "quick_play")]
+    quick_play_multiplayer: Option<String>,
+    #[arg(long, value_name = "REALM_ID", group = "quick_play")]
+    quick_play_realms: Option<String>,
+    #[arg(long, allow_hyphen_values = true)]
+    wrapper: Option<String>,
+    #[arg(long)]
+    pre_launch_hook: Option<String>,
+    #[arg(long)]
+    post_exit_hook: Option<String>,
+    // Print the launch command instead of starting the game
+    #[arg(long, action)]
+    dry_run: bool,
+    // Write the launch command to an executable script instead of starting the game
+    #[arg(long, value_name = "PATH")]
+    write_script: Option<String>,
+    // Don't redact the access token in the printed command or the script
+    #[arg(long, action)]
+    show_token: bool,
+    // Log in with a Microsoft account through a code entered in the browser, instead of starting the game
+    #[arg(long, action)]
+    login: bool,
     #[arg(short, long, action)]
     no_gui: bool,
     #[arg(long, action)]
@@ -59,14 +90,48 @@ impl Args {
         self.height
     }
 
-    pub fn dir(&self) -> &Path {
-        Path::new(&self.dir)
+    pub fn dir(&self) -> PathBuf {
+        expand_path(&self.dir)
     }
 
     pub fn memory(&self) -> u16 {
         self.memory
     }
 
+    pub fn java(&self) -> Option<&Path> {
+        self.java.as_ref().map(Path::new)
+    }
+
+    pub fn jvm_args(&self) -> Option<&str> {
+        self.jvm_args.as_deref()
+    }
+
+    pub fn quick_play(&self) -> Option<QuickPlay> {
+        self.quick_play_singleplayer.clone().map(QuickPlay::Singleplayer)
+            .or_else(|| self.quick_play_multiplayer.clone().map(QuickPlay::Multiplayer))
+            .or_else(|| self.quick_play_realms.clone().map(QuickPlay::Realms))
+    }
+
+    pub fn hooks(&self) -> LaunchHooks {
+        LaunchHooks::new(self.profile_name.clone().unwrap_or_default(), self.wrapper.clone(), self.pre_launch_hook.clone(), self.post_exit_hook.clone())
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run || self.write_script.is_some()
+    }
+
+    pub fn write_script(&self) -> Option<&Path> {
+        self.write_script.as_ref().map(Path::new)
+    }
+
+    pub fn show_token(&self) -> bool {
+        self.show_token
+    }
+
+    pub fn login(&self) -> bool {
+        self.login
+    }
+
     pub fn no_gui(&self) -> bool {
         self.no_gui
     }
@@ -79,14 +144,50 @@ impl Args {
 pub fn cli_main() -> bool {
     let args = Args::parse();
 
+    if args.login() {
+        let logged_in = login_with_device_code(|code| {
+            println!("To sign in, open {} and enter the code {}", code.verification_uri(), code.user_code());
+        });
+        match logged_in {
+            Ok(()) => println!("Logged in successfully"),
+            Err(e) => eprintln!("Error logging in! {}", e),
+        }
+        return false;
+    }
+
     if !args.no_gui() {
         return true;
     }
 
-    let launched = launch_game(args.game_version(), args.loader(), args.loader_version(), args.width(), args.height(), args.dir(), args.memory());
+    let quick_play = args.quick_play();
+    if args.dry_run() {
+        let command = get_launch_command(args.game_version(), args.loader(), args.loader_version(), args.width(), args.height(), &args.dir(), args.memory(), args.java(), args.jvm_args(), quick_play.as_ref(), &args.hooks());
+        match command {
+            Ok(command) => {
+                println!("{}", command.to_display_string(args.show_token()));
+                if let Some(path) = args.write_script() {
+                    match command.write_shell_script(path, args.show_token()) {
+                        Ok(()) => println!("Wrote launch script to {}", path.display()),
+                        Err(e) => eprintln!("Error writing launch script! {}", e),
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Error resolving launch command! {}", e)
+            }
+        }
+        return false;
+    }
+
+    let launched = launch_game(None, args.game_version(), args.loader(), args.loader_version(), args.width(), args.height(), &args.dir(), args.memory(), args.java(), args.jvm_args(), quick_play.as_ref(), &args.hooks());
     match launched {
-        Ok(()) => {
-            println!("Launching...")
+        Ok(instance) => {
+            println!("Launching... (pid {})", instance.pid());
+            let exit = instance.wait_for_exit();
+            match exit.exit_code() {
+                Some(code) => println!("Game exited with code {}", code),
+                None => println!("Game was terminated"),
+            }
         },
         Err(e) => {
             eprintln!("Error launching game! {}", e)
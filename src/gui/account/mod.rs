@@ -7,7 +7,7 @@ use crate::util::ref_comparison;
 use crate::gui::WORKER_THREAD_HANDLE;
 use iced::alignment::Horizontal;
 use iced::widget::horizontal_rule;
-use iced::widget::{button, column, container, row, text, Column, Scrollable, Space, TextInput};
+use iced::widget::{button, column, container, row, text, Column, Scrollable, Space};
 use iced::{color, Element, Length, theme};
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, mpsc, Mutex};
@@ -16,12 +16,10 @@ use crate::launcher_rewrite::error::LauncherError;
 
 #[derive(Clone, Debug)]
 pub enum AccountInteraction {
-    UsernameChanged(String),
-    PasswordChanged(String),
     LoginSubmit,
+    DeviceCodeReceived { user_code: String, verification_uri: String },
+    OpenVerificationUri,
     LoginSuccess,
-    _2FARequired,
-    InvalidCreds,
     LoginError(Arc<LauncherError>),
     Logout(String),
     LogoutAll,
@@ -32,23 +30,18 @@ pub enum AccountInteraction {
 #[derive(Debug, Clone)]
 pub enum AccountMenu {
     List,
-    Login { username: String, password: String, waiting_for_sign_in: bool, show_invalid_creds_text: bool },
-    _2FA { text: String },
+    // The user code and verification uri are only known once the login was started
+    Login { device_code: Option<(String, String)>, waiting_for_sign_in: bool, error: Option<String> },
 }
 
 impl AccountMenu {
     pub fn login() -> Self {
         Self::Login {
-            username: Default::default(),
-            password: Default::default(),
+            device_code: None,
             waiting_for_sign_in: false,
-            show_invalid_creds_text: false,
+            error: None,
         }
     }
-
-    pub fn _2fa() -> Self {
-        Self::_2FA { text: Default::default() }
-    }
 }
 
 impl Default for AccountMenu {
@@ -71,8 +64,7 @@ impl AccountTabState {
     pub fn account_data_tab(&self) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
         match &self.menu {
             AccountMenu::List => self.accounts_list(),
-            AccountMenu::Login { username, password, waiting_for_sign_in, show_invalid_creds_text } => self.sign_in(username.as_str(), password.as_str(), *waiting_for_sign_in, *show_invalid_creds_text),
-            AccountMenu::_2FA { text } => self.sign_in_2fa(text.as_str()),
+            AccountMenu::Login { device_code, waiting_for_sign_in, error } => self.sign_in(device_code.as_ref(), *waiting_for_sign_in, error.as_deref()),
         }
     }
 
@@ -100,7 +92,7 @@ impl AccountTabState {
 
         let row = row![
             Space::new(SIDE_SPACER, Length::Fill),
-            container(text(acc.minecraft_account_info().name().to_owned())).width(Length::FillPortion(1)).center_y(Length::Fill).align_x(Horizontal::Left),
+            container(text(if acc.is_demo_user() { format!("{} (Demo)", acc.minecraft_account_info().name()) } else { acc.minecraft_account_info().name().to_owned() })).width(Length::FillPortion(1)).center_y(Length::Fill).align_x(Horizontal::Left),
             container(active_text).width(Length::FillPortion(1)).center_y(Length::Fill).align_x(Horizontal::Left),
             container(use_button).width(Length::Shrink).center_y(Length::Fill),
             Space::new(5, Length::Fill),
@@ -113,32 +105,35 @@ impl AccountTabState {
         column![row, horizontal_separator(),].width(Length::Fill).height(50).into()
     }
 
-    pub fn sign_in(&self, username: &str, password: &str, waiting_for_sign_in: bool, show_wrong_creds_text: bool) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
+    pub fn sign_in(&self, device_code: Option<&(String, String)>, waiting_for_sign_in: bool, error: Option<&str>) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
         let login_text = container(text("Log in to Microsoft Account")).center_x(400);
-        let wrong_creds_text: Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> = if show_wrong_creds_text {
-            column![container(
-                text("Wrong username or password!").color(color!(255, 0, 0))
-                    .width(300),
-            )
-            .width(400),
-            Space::new(Length::Shrink, 9),
-            ]
-            .into()
-        } else {
-            Space::new(0, 0).into()
+        let error_text: Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> = match error {
+            Some(error) => column![container(text(error.to_owned()).color(color!(255, 0, 0)).width(300)).width(400), Space::new(Length::Shrink, 9),].into(),
+            None => Space::new(0, 0).into(),
+        };
+
+        let code_info: Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> = match device_code {
+            Some((user_code, verification_uri)) => column![
+                container(text(format!("Open {} and enter the code", verification_uri))).center_x(400),
+                Space::new(Length::Shrink, 10),
+                container(text(user_code.clone()).size(36)).center_x(400),
+                Space::new(Length::Shrink, 10),
+                container(button("Open Browser").padding(5).on_press(LauncherMessage::AccountTabInteraction(AccountInteraction::OpenVerificationUri))).center_x(400),
+                Space::new(Length::Shrink, 10),
+                container(text("Waiting for you to sign in...")).center_x(400),
+            ].into(),
+            None if waiting_for_sign_in => container(text("Requesting login code...")).center_x(400).into(),
+            None => column![
+                container(text("You'll get a code to enter in your browser, on this or any other device.")).center_x(400),
+                Space::new(Length::Shrink, 10),
+                container(button("Sign In").padding(5).on_press(LauncherMessage::AccountTabInteraction(AccountInteraction::LoginSubmit))).center_x(400),
+            ].into(),
         };
-        let username_input = TextInput::new("Email", username).width(400).on_input(|s| LauncherMessage::AccountTabInteraction(AccountInteraction::UsernameChanged(s)));
-        let password_input = TextInput::new("Password", password).secure(true).width(400).on_input(|s| LauncherMessage::AccountTabInteraction(AccountInteraction::PasswordChanged(s)));
-        let mut raw_login_button = button("Submit").padding(5);
-        if !waiting_for_sign_in {
-            raw_login_button = raw_login_button.on_press(LauncherMessage::AccountTabInteraction(AccountInteraction::LoginSubmit));
-        }
-        let submit_button = container(raw_login_button).center_x(400);
 
         let bottom_row = container(button("X").on_press(LauncherMessage::AccountTabInteraction(AccountInteraction::ChangeMenu(AccountMenu::List)))).width(Length::Fill).align_x(Horizontal::Left);
 
         column![
-            container(Into::<Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer>>::into(column![login_text, Space::new(Length::Shrink, 7), wrong_creds_text, username_input, Space::new(Length::Shrink, 10), password_input, Space::new(Length::Shrink, 5), submit_button,]))
+            container(Into::<Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer>>::into(column![login_text, Space::new(Length::Shrink, 7), error_text, code_info,]))
                 .center_x(Length::Fill)
                 .center_y(Length::Fill),
             bottom_row,
@@ -148,33 +143,30 @@ impl AccountTabState {
         .into()
     }
 
-    pub fn sign_in_2fa(&self, text_field: &str) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
-        text("2FA WIP").into()
-    }
-
     pub fn on_message(&mut self, action: AccountInteraction) {
         match action {
-            AccountInteraction::UsernameChanged(u) => {
-                if let AccountMenu::Login { username, password: _, .. } = &mut self.menu {
-                    *username = u;
-                }
-            }
-            AccountInteraction::PasswordChanged(p) => {
-                if let AccountMenu::Login { username: _, password, .. } = &mut self.menu {
-                    *password = p;
-                }
-            }
             AccountInteraction::LoginSubmit => 'login_submit: {
-                if let AccountMenu::Login { username, password, waiting_for_sign_in, .. } = &mut self.menu {
+                if let AccountMenu::Login { waiting_for_sign_in, error, .. } = &mut self.menu {
                     if *waiting_for_sign_in {
                         break 'login_submit;
                     }
 
                     *waiting_for_sign_in = true;
+                    *error = None;
 
-                    //println!("Username: {},\nPassword: {}", username, password);
-
-                    WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::MicrosoftLogin { username: username.clone(), password: password.clone() }).unwrap()
+                    WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::MicrosoftLogin).unwrap()
+                }
+            }
+            AccountInteraction::DeviceCodeReceived { user_code, verification_uri } => {
+                if let AccountMenu::Login { device_code, .. } = &mut self.menu {
+                    *device_code = Some((user_code, verification_uri));
+                }
+            }
+            AccountInteraction::OpenVerificationUri => {
+                if let AccountMenu::Login { device_code: Some((_, verification_uri)), .. } = &self.menu {
+                    let _ = open::that(verification_uri).inspect_err(|e| {
+                        eprintln!("Failed to open browser: {e}");
+                    });
                 }
             }
             AccountInteraction::LoginSuccess => {
@@ -197,18 +189,13 @@ impl AccountTabState {
             AccountInteraction::ChangeMenu(m) => {
                 self.menu = m;
             }
-            AccountInteraction::_2FARequired => {
-                self.menu = AccountMenu::_2fa();
-            }
-            AccountInteraction::InvalidCreds => {
-                if let AccountMenu::Login { username, password: pass, waiting_for_sign_in, show_invalid_creds_text } = &mut self.menu {
+            AccountInteraction::LoginError(e) => {
+                if let AccountMenu::Login { device_code, waiting_for_sign_in, error } = &mut self.menu {
+                    *device_code = None;
                     *waiting_for_sign_in = false;
-                    *show_invalid_creds_text = true;
+                    *error = Some(e.to_string());
                 }
             }
-            AccountInteraction::LoginError(e) => {
-                // Nothing for now
-            }
         }
     }
 }
@@ -0,0 +1,100 @@
+use std::fs;
+use std::sync::Arc;
+use iced::{Element, Length, Size, window};
+use iced::widget::{button, column, container, row, scrollable, text, Space};
+use crate::gui::{LauncherRenderer, LauncherTheme};
+use crate::gui::style::{dark_container_style, generic_button_style};
+use crate::launcher_rewrite::crash::GameExit;
+use crate::launcher_rewrite::game_output::GameOutput;
+
+const LAST_LOG_LINES: usize = 40;
+
+pub fn crash_report_window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(960_f32, 720_f32),
+        resizable: true,
+        decorations: true,
+        ..Default::default()
+    }
+}
+
+#[derive(Debug)]
+pub struct CrashReportGuiState {
+    output: Arc<GameOutput>,
+    exit: GameExit,
+    report: Option<String>,
+    last_lines: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum CrashReportMessage {
+    OpenFolder,
+    ShowGameOutput,
+}
+
+impl CrashReportGuiState {
+
+    pub fn new(output: Arc<GameOutput>, exit: GameExit) -> Self {
+        let report = exit.crash_report().map(|path| fs::read(path).map(|bytes| String::from_utf8_lossy(&bytes).into_owned()).unwrap_or_else(|e| format!("Failed to read crash report {}: {}", path.display(), e)));
+        let last_lines = output.last_lines(LAST_LOG_LINES).join("\n");
+        Self { output, exit, report, last_lines }
+    }
+
+    pub fn title(&self) -> String {
+        format!("Minecraft {} Crashed", self.output.version_id())
+    }
+
+    pub fn output(&self) -> &Arc<GameOutput> {
+        &self.output
+    }
+
+    pub fn update(&mut self, message: CrashReportMessage) {
+        match message {
+            CrashReportMessage::OpenFolder => {
+                let _ = open::that(self.exit.report_folder()).inspect_err(|e| {
+                    eprintln!("Failed to open crash report folder: {e}");
+                });
+            }
+            // Opening the output window is handled by the gui, since it needs a new window
+            CrashReportMessage::ShowGameOutput => {}
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, CrashReportMessage, LauncherTheme, LauncherRenderer> {
+        let exit_code = match self.exit.exit_code() {
+            Some(code) => format!("The game exited with code {}.", code),
+            None => String::from("The game was terminated without an exit code."),
+        };
+
+        let report_header = match self.exit.crash_report() {
+            Some(path) => format!("Crash Report: {}", path.display()),
+            None => String::from("No crash report was written"),
+        };
+
+        let mut content = column![
+            container(text(exit_code).size(20)).padding(10),
+            section_header(report_header),
+        ].width(Length::Fill).height(Length::Fill);
+
+        if let Some(report) = &self.report {
+            content = content.push(scrollable(container(text(report.as_str()).size(14)).padding(10).width(Length::Fill)).height(Length::FillPortion(3)));
+        }
+
+        let buttons = container(row![
+            button("Open Folder").padding(10).style(generic_button_style).on_press(CrashReportMessage::OpenFolder),
+            button("Show Game Output").padding(10).style(generic_button_style).on_press(CrashReportMessage::ShowGameOutput),
+        ].spacing(10)).padding(10).style(dark_container_style).width(Length::Fill);
+
+        content
+            .push(section_header(String::from("Last Log Lines")))
+            .push(scrollable(container(text(self.last_lines.as_str()).size(14)).padding(10).width(Length::Fill)).anchor_bottom().height(Length::FillPortion(2)))
+            .push(Space::new(Length::Fill, 5))
+            .push(buttons)
+            .into()
+    }
+
+}
+
+fn section_header(content: String) -> Element<'static, CrashReportMessage, LauncherTheme, LauncherRenderer> {
+    container(text(content)).padding(5).center_x(Length::Fill).style(dark_container_style).into()
+}
@@ -1,77 +1,179 @@
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
-use iced::{Element, Size, Subscription, Task, time, window};
-use iced::widget::text;
-use crate::gui::{LauncherRenderer, LauncherTheme, MC_FONT};
-use crate::gui::MC_FONT_BYTES;
-
-#[must_use]
-pub fn open_game_output_window() -> Arc<Mutex<String>> {
-    let window = GameOutputGuiState::new();
-    let handle = window.get_output();
-    thread::Builder::new().name("Game Output GUI Thread".to_owned()).spawn(move || {
-        let window_settings = window::Settings {
-            size: Size::new(1280_f32, 720_f32),
-            resizable: true,
-            decorations: true,
-            ..Default::default()
-        };
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::Arc;
+use iced::{clipboard, Element, Length, Size, Task, window};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input, Column, PickList, Space};
+use iced::widget::scrollable::RelativeOffset;
+use native_dialog::{FileDialog, MessageDialog, MessageType};
+use crate::gui::{LauncherRenderer, LauncherTheme};
+use crate::gui::style::{dark_container_style, generic_button_style};
+use crate::launcher_rewrite::game_output::{GameOutput, LogLevel, OutputLine};
 
+// Rendering every buffered line at once makes the window crawl, so only the newest matches are shown. Copy and save still use everything.
+const MAX_DISPLAYED_LINES: usize = 1000;
 
-        iced::application(GameOutputGuiState::title, GameOutputGuiState::update, GameOutputGuiState::view)
-            .window(window_settings)
-            .font(MC_FONT_BYTES)
-            .default_font(MC_FONT)
-            .subscription(GameOutputGuiState::subscription)
-            .run()
-            .expect("Failed to load game output gui")
-    }).expect("Failed to start game output gui thread");
-    handle
+pub fn game_output_window_settings() -> window::Settings {
+    window::Settings {
+        size: Size::new(1280_f32, 720_f32),
+        resizable: true,
+        decorations: true,
+        ..Default::default()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct GameOutputGuiState {
-    output: Arc<Mutex<String>>,
+    output: Arc<GameOutput>,
+    search: String,
+    // Lowercased once when the search changes instead of for every line
+    search_lowercase: String,
+    // Lines below this level are hidden
+    min_level: LogLevel,
+    autoscroll: bool,
+    last_received: usize,
+    // Numbers of the lines that match the filters, counting every line ever received so that way they stay valid when old lines are dropped
+    filtered: VecDeque<usize>,
+    // Lines before this number have already been checked against the filters
+    filtered_up_to: usize,
+    scrollable_id: scrollable::Id,
 }
 
 #[derive(Debug, Clone)]
 pub enum GameOutputMessage {
     OutputReceived,
+    SearchChanged(String),
+    MinLevelChanged(LogLevel),
+    AutoscrollToggled(bool),
+    CopyToClipboard,
+    SaveToFile,
 }
 
 impl GameOutputGuiState {
 
-    fn new() -> Self {
-        Self {
-            output: Arc::new(Mutex::new(String::new()))
-        }
-    }
-
-    fn get_output(&self) -> Arc<Mutex<String>> {
-        self.output.clone()
+    pub fn new(output: Arc<GameOutput>) -> Self {
+        let mut state = Self {
+            output,
+            search: String::new(),
+            search_lowercase: String::new(),
+            min_level: LogLevel::Trace,
+            autoscroll: true,
+            last_received: 0,
+            filtered: VecDeque::new(),
+            filtered_up_to: 0,
+            scrollable_id: scrollable::Id::unique(),
+        };
+        state.update_filtered();
+        state
     }
 
-    fn title(&self) -> String {
-        String::from("Minecraft Game Output")
+    pub fn title(&self) -> String {
+        let status = if self.output.is_running() { "" } else { " (Stopped)" };
+        format!("Minecraft {} Game Output{}", self.output.version_id(), status)
     }
 
     pub fn update(&mut self, message: GameOutputMessage) -> Task<GameOutputMessage> {
+        match message {
+            GameOutputMessage::OutputReceived => {
+                let received = self.output.received();
+                if received != self.last_received {
+                    self.last_received = received;
+                    self.update_filtered();
+                    if self.autoscroll {
+                        return scrollable::snap_to(self.scrollable_id.clone(), RelativeOffset::END);
+                    }
+                }
+            }
+            GameOutputMessage::SearchChanged(search) => {
+                self.search_lowercase = search.to_lowercase();
+                self.search = search;
+                self.refilter();
+            }
+            GameOutputMessage::MinLevelChanged(level) => {
+                self.min_level = level;
+                self.refilter();
+            }
+            GameOutputMessage::AutoscrollToggled(autoscroll) => {
+                self.autoscroll = autoscroll;
+                if autoscroll {
+                    return scrollable::snap_to(self.scrollable_id.clone(), RelativeOffset::END);
+                }
+            }
+            GameOutputMessage::CopyToClipboard => {
+                return clipboard::write(self.filtered_text());
+            }
+            GameOutputMessage::SaveToFile => {
+                let path = FileDialog::new().set_filename("game_output.log").add_filter("Log File", &["log", "txt"]).show_save_single_file();
+                if let Ok(Some(path)) = path {
+                    if let Err(e) = fs::write(&path, self.filtered_text()) {
+                        let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to save game output").set_text(e.to_string().as_str()).show_alert();
+                    }
+                }
+            }
+        }
         Task::none()
     }
 
-    fn view(&self) -> Element<'_, GameOutputMessage, LauncherTheme, LauncherRenderer> {
-        text(self.output.lock().unwrap().as_str().to_owned()).into()
+    pub fn view(&self) -> Element<'_, GameOutputMessage, LauncherTheme, LauncherRenderer> {
+        let toolbar = container(row![
+            text_input("Search", self.search.as_str()).on_input(GameOutputMessage::SearchChanged).width(Length::Fill),
+            PickList::new(LogLevel::ALL, Some(self.min_level), GameOutputMessage::MinLevelChanged),
+            container(checkbox("Autoscroll", self.autoscroll).on_toggle(GameOutputMessage::AutoscrollToggled)).center_y(Length::Fill),
+            button("Copy").style(generic_button_style).on_press(GameOutputMessage::CopyToClipboard),
+            button("Save").style(generic_button_style).on_press(GameOutputMessage::SaveToFile),
+        ].spacing(10).height(Length::Shrink)).padding(10).style(dark_container_style);
+
+        let shown: Vec<Element<'_, GameOutputMessage, LauncherTheme, LauncherRenderer>> = self.filtered_lines(MAX_DISPLAYED_LINES).into_iter().map(|l| text(l).size(14).into()).collect();
+
+        let output = scrollable(Column::with_children(shown).padding(10).width(Length::Fill)).id(self.scrollable_id.clone()).height(Length::Fill).width(Length::Fill);
+
+        column![toolbar, output, Space::new(Length::Fill, 5)].into()
+    }
+
+    fn matches(&self, line: &OutputLine) -> bool {
+        if line.level() < self.min_level {
+            return false;
+        }
+        if self.search_lowercase.is_empty() {
+            return true;
+        }
+        let search = self.search_lowercase.as_str();
+        line.text().to_lowercase().contains(search) || line.event().is_some_and(|e| e.logger().to_lowercase().contains(search))
     }
 
-    pub fn subscription(&self) -> Subscription<GameOutputMessage> {
-        time::every(Duration::from_millis(100)).map(|_i| GameOutputMessage::OutputReceived)
+    ///
+    /// Checks the lines received since the last call against the filters
+    ///
+    fn update_filtered(&mut self) {
+        let lines = self.output.lines();
+        // The buffer only changes while its lock is held, so this is the number of the oldest line still in it
+        let first = self.output.received() - lines.len();
+        let start = self.filtered_up_to.max(first);
+        let matching: Vec<usize> = lines.iter().enumerate().skip(start - first).filter(|(_, l)| self.matches(l)).map(|(i, _)| first + i).collect();
+        self.filtered_up_to = first + lines.len();
+        drop(lines);
+        self.filtered.extend(matching);
+        while self.filtered.front().is_some_and(|&n| n < first) {
+            self.filtered.pop_front();
+        }
     }
 
-}
+    fn refilter(&mut self) {
+        self.filtered.clear();
+        self.filtered_up_to = 0;
+        self.update_filtered();
+    }
+
+    ///
+    /// Text of the newest `n` matching lines, oldest first
+    ///
+    fn filtered_lines(&self, n: usize) -> Vec<String> {
+        let lines = self.output.lines();
+        let first = self.output.received() - lines.len();
+        self.filtered.iter().skip(self.filtered.len().saturating_sub(n)).filter_map(|&number| lines.get(number.checked_sub(first)?)).map(|l| l.text().to_owned()).collect()
+    }
 
-impl Default for GameOutputGuiState {
-    fn default() -> Self {
-        Self::new()
+    fn filtered_text(&self) -> String {
+        self.filtered_lines(usize::MAX).iter().map(String::as_str).intersperse("\n").collect()
     }
-}
\ No newline at end of file
+
+}
@@ -1,12 +1,12 @@
 use crate::gui::je::installations::horizontal_separator;
-use crate::gui::je::JavaEditionTab;
+use crate::gui::je::{JavaEditionTab, JeGuiInteraction};
 use crate::gui::{GuiMessage, LauncherMessage, LauncherRenderer, LauncherTheme};
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::profiles::icon::LauncherProfileIcon;
-use crate::launcher_rewrite::profiles::{LauncherProfile, ModLoader};
+use crate::launcher_rewrite::profiles::{LauncherProfile, ModLoader, QuickPlay, PROFILES};
 use iced::alignment::Horizontal;
 use iced::widget::image::FilterMethod;
-use iced::widget::{button, column, container, image, row, text, PickList, Scrollable};
+use iced::widget::{button, checkbox, column, container, image, row, text, PickList, Scrollable};
 use iced::widget::{text_input, Space};
 use iced::{Element, Font, Length};
 use iced_aw::{helpers, number_input};
@@ -23,12 +23,22 @@ pub enum JeProfileChanged {
     WidthChanged(String),
     HeightChanged(String),
     JvmArgsChanged(String),
+    JavaExecutableChanged(String),
+    QuickPlayTypeChanged(QuickPlay),
+    QuickPlayTargetChanged(String),
+    OpenOutputAtLaunchChanged(bool),
+    WrapperCommandChanged(String),
+    PreLaunchHookChanged(String),
+    PostExitHookChanged(String),
+    IsolatedInstanceChanged(bool),
+    LinkSharedFoldersChanged(bool),
     IconChanged(LauncherProfileIcon),
     LoaderVersionChanged(String),
     Save,
+    Delete,
 }
 
-pub fn edit_installations_tab_content(profile: &LauncherProfile) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
+pub fn edit_installations_tab_content(profile: &LauncherProfile, java_installations: &[String]) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
     let icons = [
         LauncherProfileIcon::Grass,
         LauncherProfileIcon::Dirt,
@@ -130,7 +140,7 @@ pub fn edit_installations_tab_content(profile: &LauncherProfile) -> Element<'sta
     ].height(40)).center_x(Length::Fill);
     let mod_loader_picker = container(row![container(text("Mod Loader: ")).center_y(Length::Fill).width(240), container(PickList::new(mod_loader_list, Some(profile.mod_loader()), |loader| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::ModLoaderChanged(loader)))).center_y(Length::Fill).width(240),].height(40)).center_x(Length::Fill);
 
-    let mut loader_version_list = profile.mod_loader().get_manifest().map(|m| m.get_loader_versions(GAME_VERSION_MANIFEST.sanitize_version_name(profile.version_name(), profile.mod_loader())).iter().map(|v| v.version_name().to_owned()).collect()).unwrap_or(Vec::new()); //["latest-stable".to_owned(), "latest-beta".to_owned()];
+    let mut loader_version_list = profile.mod_loader().get_manifest().map(|m| m.get_loader_versions(GAME_VERSION_MANIFEST.sanitize_version_name(profile.version_name(), profile.mod_loader())).map(|versions| versions.iter().map(|v| v.version_name().to_owned()).collect()).unwrap_or(Vec::new())).unwrap_or(Vec::new()); //["latest-stable".to_owned(), "latest-beta".to_owned()];
     if !loader_version_list.is_empty() {
         loader_version_list.insert(0, LATEST_BETA_TEXT.to_owned())
     }
@@ -169,12 +179,73 @@ pub fn edit_installations_tab_content(profile: &LauncherProfile) -> Element<'sta
     )
     .center_x(Length::Fill);
 
+    let java_selector = container(
+        column![
+            container(text_input("<Use Recommended Runtime>", profile.java_executable().unwrap_or_default()).width(460).on_input(|s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::JavaExecutableChanged(s)))).width(480),
+            Space::new(Length::Fill, 11),
+            container(PickList::new(java_installations.to_vec(), profile.java_executable().map(String::from), |java| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::JavaExecutableChanged(java))).placeholder("Detected Java Installations").width(460)).width(480),
+        ]
+        .width(480),
+    )
+    .center_x(Length::Fill);
+
+    let quick_play_types = vec![QuickPlay::Singleplayer(String::new()), QuickPlay::Multiplayer(String::new()), QuickPlay::Realms(String::new())];
+    let quick_play_placeholder = match profile.quick_play() {
+        Some(QuickPlay::Singleplayer(_)) => "World Folder Name",
+        Some(QuickPlay::Multiplayer(_)) => "host:port",
+        Some(QuickPlay::Realms(_)) => "Realm Id",
+        None => "<Title Screen>",
+    };
+    let quick_play_selector = container(row![
+        container(PickList::new(quick_play_types, profile.quick_play().cloned(), |q| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::QuickPlayTypeChanged(q))).placeholder("Quick Play").width(220)).center_y(Length::Fill).width(240),
+        container(text_input(quick_play_placeholder, profile.quick_play().map(|q| q.target()).unwrap_or_default()).width(220).on_input_maybe(profile.quick_play().map(|_| |s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::QuickPlayTargetChanged(s))))).center_y(Length::Fill).width(240),
+    ].height(40)).center_x(Length::Fill);
+
+    let wrapper_command = container(container(text_input("<Wrapper Command, e.g. gamemoderun>", profile.wrapper_command().unwrap_or_default()).width(460).on_input(|s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::WrapperCommandChanged(s)))).width(480)).center_x(Length::Fill);
+
+    let hooks_selector = container(
+        column![
+            container(text_input("<Pre-Launch Hook>", profile.pre_launch_hook().unwrap_or_default()).width(460).on_input(|s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::PreLaunchHookChanged(s)))).width(480),
+            Space::new(Length::Fill, 11),
+            container(text_input("<Post-Exit Hook>", profile.post_exit_hook().unwrap_or_default()).width(460).on_input(|s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::PostExitHookChanged(s)))).width(480),
+        ]
+        .width(480),
+    )
+    .center_x(Length::Fill);
+
+    let open_output_selector = container(container(checkbox("Open game output window at launch", profile.open_output_at_launch()).on_toggle(|b| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::OpenOutputAtLaunchChanged(b)))).width(480)).center_x(Length::Fill);
+
+    let instance_selector = container(
+        column![
+            checkbox("Isolated instance (own folder for mods, configs and saves)", profile.isolated_instance()).on_toggle(|b| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::IsolatedInstanceChanged(b))),
+            Space::new(Length::Fill, 11),
+            checkbox("Share screenshots, resource packs and shader packs with the Minecraft directory", profile.link_shared_folders()).on_toggle_maybe(profile.isolated_instance().then_some(|b| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::LinkSharedFoldersChanged(b)))),
+        ]
+        .width(480),
+    )
+    .center_x(Length::Fill);
+
     let additional_jvm_args = container(container(text_input("<JVM Arguments>", profile.additional_args().as_ref().unwrap_or(&String::default()).as_str()).width(460).on_input(|s| GuiMessage::JavaEditionProfileChanged(JeProfileChanged::JvmArgsChanged(s)))).width(480)).center_x(Length::Fill);
 
+    let launch_command_buttons = container(
+        container(
+            row![
+                container(button("Show Launch Command").padding(10).on_press(GuiMessage::JavaEditionInteraction(JeGuiInteraction::ShowLaunchCommand))).width(Length::Fill).align_x(Horizontal::Left),
+                container(button("Export Launch Script").padding(10).on_press(GuiMessage::JavaEditionInteraction(JeGuiInteraction::ExportLaunchScript))).width(Length::Fill).align_x(Horizontal::Right),
+            ]
+            .width(460),
+        )
+        .width(480),
+    )
+    .center_x(Length::Fill);
+
+    // New profiles don't exist until they are saved, so there is nothing to delete yet
+    let is_saved = PROFILES.read().unwrap().find_profile(profile.id()).is_some();
     let save_cancel_buttons = container(
         container(
             row![
                 container(button("Cancel").padding(10).on_press(GuiMessage::JavaEditionSelectTab(JavaEditionTab::Installations))).width(Length::Fill).align_x(Horizontal::Left),
+                container(button("Delete").padding(10).on_press_maybe(is_saved.then_some(GuiMessage::JavaEditionProfileChanged(JeProfileChanged::Delete)))).width(Length::Fill).align_x(Horizontal::Center),
                 container(button("Save").padding(10).on_press(GuiMessage::JavaEditionProfileChanged(JeProfileChanged::Save))).width(Length::Fill).align_x(Horizontal::Right),
             ]
             .width(460),
@@ -209,10 +280,32 @@ pub fn edit_installations_tab_content(profile: &LauncherProfile) -> Element<'sta
         Space::new(Length::Fill, 11),
         resolution_selector,
         Space::new(Length::Fill, 15),
+        container(text("Java Executable:")).height(40).center_x(Length::Fill),
+        Space::new(Length::Fill, 11),
+        java_selector,
+        Space::new(Length::Fill, 15),
+        container(text("Launch Directly Into:")).height(40).center_x(Length::Fill),
+        Space::new(Length::Fill, 11),
+        quick_play_selector,
+        Space::new(Length::Fill, 15),
         container(text("Additional Jvm Args:")).height(40).center_x(Length::Fill),
         Space::new(Length::Fill, 11),
         additional_jvm_args,
-        Space::new(Length::Fill, 60),
+        Space::new(Length::Fill, 15),
+        container(text("Wrapper Command:")).height(40).center_x(Length::Fill),
+        Space::new(Length::Fill, 11),
+        wrapper_command,
+        Space::new(Length::Fill, 15),
+        container(text("Launch Hooks:")).height(40).center_x(Length::Fill),
+        Space::new(Length::Fill, 11),
+        hooks_selector,
+        Space::new(Length::Fill, 15),
+        open_output_selector,
+        Space::new(Length::Fill, 13),
+        instance_selector,
+        Space::new(Length::Fill, 30),
+        launch_command_buttons,
+        Space::new(Length::Fill, 30),
         save_cancel_buttons,
         Space::new(Length::Fill, 150),
     ]
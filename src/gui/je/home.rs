@@ -1,5 +1,6 @@
 use crate::gui::je::{JeGuiInteraction, JeGuiState};
 use crate::gui::{LauncherMessage, LauncherRenderer, LauncherTheme, MC_FONT};
+use chrono::Local;
 use iced::alignment::{Horizontal, Vertical};
 use iced::futures::future::select;
 use iced::widget::{button, column, container, image, row, text, Column, PickList, Scrollable, Space, markdown, rich_text, scrollable};
@@ -13,7 +14,9 @@ use crate::gui::style::{dark_container_style, play_button_style};
 use crate::launcher_rewrite::launch_properties::Rule;
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::patch_notes::JAVA_EDITION_PATCH_NOTES;
+use crate::launcher_rewrite::instances::RUNNING_INSTANCES;
 use crate::launcher_rewrite::profiles::{LauncherProfile, PROFILES};
+use crate::launcher_rewrite::profiles::play_history::{format_playtime, PLAY_HISTORY};
 
 #[derive(Clone, PartialEq)]
 pub struct ProfileSelectorElement {
@@ -60,7 +63,22 @@ pub fn home_tab_content(state: &JeGuiState) -> Element<'static, LauncherMessage,
 
     //let dropdown = DropDown::new(profile, profile_picker, true);
 
-    let play_button_row = container(row![container(dropdown).center_y(Length::Fill).center_x(Length::Fill), play_button, Space::new(Length::Fill, Length::Shrink),].width(Length::Fill).height(100)).style(dark_container_style);
+    let output_button: Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> = match state.last_game_output() {
+        Some(output) => button("Game Output").padding(10).on_press(LauncherMessage::OpenGameOutput(output.clone())).into(),
+        None => Space::new(Length::Shrink, Length::Shrink).into(),
+    };
+
+    let running = RUNNING_INSTANCES.find_by_profile(state.selected_profile_id());
+    let stop_button = button("Stop").padding(10).on_press_maybe((!running.is_empty()).then_some(LauncherMessage::JavaEditionInteraction(JeGuiInteraction::StopGame)));
+    let running_status = running.first().map(|i| format!("Running as {} since {} (pid {})", i.account_name(), i.start_time().with_timezone(&Local).format("%H:%M"), i.pid())).unwrap_or_default();
+
+    let play_stats = PLAY_HISTORY.read().unwrap().stats(state.selected_profile_id()).cloned();
+    let play_stats_text = match play_stats.and_then(|s| s.last_played().map(|last| (last, s.total_playtime_secs()))) {
+        Some((last_played, playtime)) => format!("Last played {} · {} played", last_played.with_timezone(&Local).format("%Y-%m-%d %H:%M"), format_playtime(playtime)),
+        None => String::from("Not played yet"),
+    };
+
+    let play_button_row = container(row![container(column![dropdown, text(play_stats_text).size(14)].spacing(5)).center_y(Length::Fill).center_x(Length::Fill), play_button, container(column![row![output_button, stop_button].spacing(10), text(running_status).size(14)].spacing(5)).center_y(Length::Fill).center_x(Length::Fill),].width(Length::Fill).height(100)).style(dark_container_style);
 
     let patch_notes_bar = nice_header("Patch Notes", 30f32);
 
@@ -5,8 +5,10 @@ use iced::alignment::Horizontal;
 use iced::widget::{button, column, container, horizontal_rule, image, row, text, text_input, vertical_rule, Column, Image, Scrollable, Space};
 use iced::{ContentFit, Element, Length};
 use std::fmt::format;
+use chrono::Local;
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::profiles::{LauncherProfile, PROFILES};
+use crate::launcher_rewrite::profiles::play_history::{format_playtime, LaunchRecord, PLAY_HISTORY};
 
 pub const SIDE_SPACER: u16 = 7;
 
@@ -41,12 +43,33 @@ fn map_profile_to_widget(profile: &LauncherProfile) -> Element<'static, Launcher
 
     let version_text = container(text(GAME_VERSION_MANIFEST.get_version_from_str(profile.version_name()).expect("profile has bad version id!").id())).center_x(Length::FillPortion(3)).center_y(Length::Fill);
 
+    let play_stats = PLAY_HISTORY.read().unwrap().stats(profile.id()).cloned().unwrap_or_default();
+    let last_played = play_stats.last_played().map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()).unwrap_or(String::from("Never"));
+    let play_stats_text = container(column![
+        text(format!("Last played: {}", last_played)).size(14),
+        text(format!("Playtime: {}", format_playtime(play_stats.total_playtime_secs()))).size(14),
+        text(play_stats.history().front().map(last_session_text).unwrap_or_default()).size(14),
+    ]).center_x(Length::FillPortion(3)).center_y(Length::Fill);
+
     let edit_button = container(button("Edit").on_press(GuiMessage::JavaEditionSelectTab(JavaEditionTab::EditProfile(Some(profile.id()))))).center_y(Length::Fill).align_x(Horizontal::Right).width(Length::FillPortion(1));
 
-    let profile_info = row![image, name_text, version_text, edit_button, Space::new(SIDE_SPACER, Length::Fill),].width(Length::Fill).height(130);
+    let profile_info = row![image, name_text, version_text, play_stats_text, edit_button, Space::new(SIDE_SPACER, Length::Fill),].width(Length::Fill).height(130);
     column![profile_info, horizontal_separator(),].height(Length::Shrink).width(Length::Fill).into()
 }
 
+fn last_session_text(record: &LaunchRecord) -> String {
+    let version = match record.loader_version() {
+        Some(loader_version) => format!("{} ({})", record.version(), loader_version),
+        None => record.version().to_owned(),
+    };
+    let exit = match record.exit_code() {
+        Some(0) => String::from("exited normally"),
+        Some(code) => format!("exit code {}", code),
+        None => String::from("terminated"),
+    };
+    format!("Last session: {}, {}, {}", version, format_playtime(record.duration_secs()), exit)
+}
+
 pub fn horizontal_separator() -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
     row![Space::new(SIDE_SPACER, Length::Fill), horizontal_rule(5), Space::new(SIDE_SPACER, Length::Fill),].height(Length::Shrink).into()
 }
@@ -4,6 +4,7 @@ pub mod installations;
 mod skins;
 mod create_shortcut;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use crate::gui::je::edit_installation::{edit_installations_tab_content, JeProfileChanged};
 use crate::gui::je::home::home_tab_content;
@@ -15,11 +16,23 @@ use crate::gui::WORKER_THREAD_HANDLE;
 use iced::alignment::{Horizontal, Vertical};
 use iced::widget::{button, column, container, row, text, Space, markdown};
 use iced::{Element, Length};
+use native_dialog::{FileDialog, MessageDialog, MessageType};
 use crate::gui::je::create_shortcut::{create_shortcut_gui, ShortcutInfo};
 use crate::gui::style::{dark_container_style, generic_button_style};
 use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::game_output::GameOutput;
+use crate::launcher_rewrite::instances::RUNNING_INSTANCES;
+use crate::launcher_rewrite::java::discovery::JavaInstallation;
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
-use crate::launcher_rewrite::profiles::{LauncherProfile, PROFILES};
+use crate::launcher_rewrite::path_handler::get_instance_dir;
+use crate::launcher_rewrite::profiles::instance_dir::{delete_instance_dir, sync_instance_dir};
+use crate::launcher_rewrite::profiles::{save_launcher_profiles, LauncherProfile, PROFILES};
+use crate::launcher_rewrite::util::shell_args::split_shell_args;
+
+#[cfg(target_os = "windows")]
+const LAUNCH_SCRIPT_NAME: &str = "launch.bat";
+#[cfg(not(target_os = "windows"))]
+const LAUNCH_SCRIPT_NAME: &str = "launch.sh";
 
 pub struct JeGuiState {
     current_tab: JavaEditionTab,
@@ -28,6 +41,9 @@ pub struct JeGuiState {
     selected_profile_id: u128,
     is_launching: bool,
     profile_search_content: String,
+    last_game_output: Option<Arc<GameOutput>>,
+    // Executables of the java installations found on the system, discovered again every time the editor is opened
+    java_installations: Vec<String>,
 }
 
 impl Default for JeGuiState {
@@ -39,6 +55,8 @@ impl Default for JeGuiState {
             selected_profile_id: PROFILES.read().unwrap().settings().selected_profile_id(),
             is_launching: false,
             profile_search_content: Default::default(),
+            last_game_output: None,
+            java_installations: Vec::new(),
         }
     }
 }
@@ -49,7 +67,13 @@ pub enum JeGuiInteraction {
     SearchProfiles(String),
     ClickLink(markdown::Url),
     LaunchGame,
+    StopGame,
     GameLaunchFailed(Arc<LauncherError>),
+    ShowLaunchCommand,
+    ExportLaunchScript,
+    LaunchCommandExported { command: String, script_path: Option<PathBuf> },
+    LaunchCommandFailed(Arc<LauncherError>),
+    JavaInstallationsDiscovered(Vec<JavaInstallation>),
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -87,7 +111,7 @@ impl JeGuiState {
             JavaEditionTab::Home => home_tab_content(&self),
             JavaEditionTab::Skins => skins_tab_content(),
             JavaEditionTab::Installations => installations_tab_content(self),
-            JavaEditionTab::EditProfile(id) => edit_installations_tab_content(&self.profile_edit),
+            JavaEditionTab::EditProfile(id) => edit_installations_tab_content(&self.profile_edit, &self.java_installations),
             JavaEditionTab::CreateShortcut => create_shortcut_gui(),
         }
     }
@@ -101,6 +125,44 @@ impl JeGuiState {
             JeGuiInteraction::LaunchGame => {
                 WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::LaunchGame(self.selected_profile_id)).expect("TODO: panic message");
             }
+            JeGuiInteraction::StopGame => {
+                for instance in RUNNING_INSTANCES.find_by_profile(self.selected_profile_id) {
+                    let _ = instance.force_stop().inspect_err(|e| {
+                        let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to stop game").set_text(e.to_string().as_str()).show_alert();
+                    });
+                }
+            }
+            JeGuiInteraction::ShowLaunchCommand => {
+                if !self.require_saved_profile() {
+                    return;
+                }
+                WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::ExportLaunchCommand { profile_id: self.profile_edit.id(), script_path: None, show_token: false }).expect("Worker thread stopped");
+            }
+            JeGuiInteraction::ExportLaunchScript => {
+                if !self.require_saved_profile() {
+                    return;
+                }
+                let Ok(Some(script_path)) = FileDialog::new().set_filename(LAUNCH_SCRIPT_NAME).show_save_single_file() else { return };
+                let show_token = MessageDialog::new().set_type(MessageType::Warning).set_title("Include access token?").set_text("Include your access token in the script? Anyone who can read the script could use it to log in as you. Without it, the game can't join online servers.").show_confirm().unwrap_or(false);
+                WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::ExportLaunchCommand { profile_id: self.profile_edit.id(), script_path: Some(script_path), show_token }).expect("Worker thread stopped");
+            }
+            JeGuiInteraction::LaunchCommandExported { command, script_path } => {
+                let text = match script_path {
+                    Some(path) => format!("Wrote launch script to {}", path.display()),
+                    None => command,
+                };
+                let _ = MessageDialog::new().set_type(MessageType::Info).set_title("Launch Command").set_text(text.as_str()).show_alert().inspect_err(|e| {
+                    eprintln!("Failed to show dialog: {e}");
+                });
+            }
+            JeGuiInteraction::LaunchCommandFailed(err) => {
+                let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to resolve launch command").set_text(err.to_string().as_str()).show_alert().inspect_err(|e| {
+                    eprintln!("Failed to show error dialog: {e}");
+                });
+            }
+            JeGuiInteraction::JavaInstallationsDiscovered(installations) => {
+                self.java_installations = installations.iter().map(|j| j.executable().to_string_lossy().into_owned()).collect();
+            }
             JeGuiInteraction::SearchProfiles(s) => {
                 self.profile_search_content = s;
             }
@@ -111,7 +173,25 @@ impl JeGuiState {
                 });
             }
             JeGuiInteraction::GameLaunchFailed(err) => {
-                // TODO display error nicely
+                self.is_launching = false;
+                if let LauncherError::IncompatibleJava { .. } = err.as_ref() {
+                    let use_recommended = MessageDialog::new().set_type(MessageType::Warning).set_title("Incompatible Java").set_text(format!("{}\n\nUse the recommended runtime for this installation?", err).as_str()).show_confirm().unwrap_or(false);
+                    if use_recommended {
+                        if let Some(profile) = PROFILES.write().unwrap().je_client_profiles_mut().iter_mut().find(|p| p.id() == self.selected_profile_id) {
+                            profile.set_java_executable(None);
+                        }
+                        // Otherwise saving the profile in the editor would bring the incompatible java back
+                        if self.profile_edit.id() == self.selected_profile_id {
+                            self.profile_edit.set_java_executable(None);
+                        }
+                        save_launcher_profiles();
+                        self.interact(JeGuiInteraction::LaunchGame);
+                    }
+                    return;
+                }
+                let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to launch game").set_text(err.to_string().as_str()).show_alert().inspect_err(|e| {
+                    eprintln!("Failed to show error dialog: {e}");
+                });
             }
         }
     }
@@ -152,7 +232,58 @@ impl JeGuiState {
             JeProfileChanged::JvmArgsChanged(args) => {
                 self.profile_edit.set_additional_args(if args.is_empty() { None } else { Some(args) });
             }
+            JeProfileChanged::QuickPlayTypeChanged(quick_play) => {
+                let target = self.profile_edit.quick_play().map(|q| q.target().to_owned()).unwrap_or_default();
+                self.profile_edit.set_quick_play(Some(quick_play.with_target(target)));
+            }
+            JeProfileChanged::QuickPlayTargetChanged(target) => {
+                let quick_play = self.profile_edit.quick_play().map(|q| q.with_target(target));
+                self.profile_edit.set_quick_play(quick_play);
+            }
+            JeProfileChanged::WrapperCommandChanged(wrapper) => {
+                self.profile_edit.set_wrapper_command(if wrapper.is_empty() { None } else { Some(wrapper) });
+            }
+            JeProfileChanged::PreLaunchHookChanged(hook) => {
+                self.profile_edit.set_pre_launch_hook(if hook.is_empty() { None } else { Some(hook) });
+            }
+            JeProfileChanged::PostExitHookChanged(hook) => {
+                self.profile_edit.set_post_exit_hook(if hook.is_empty() { None } else { Some(hook) });
+            }
+            JeProfileChanged::IsolatedInstanceChanged(isolated) => {
+                self.profile_edit.set_isolated_instance(isolated);
+            }
+            JeProfileChanged::LinkSharedFoldersChanged(link) => {
+                self.profile_edit.set_link_shared_folders(link);
+            }
+            JeProfileChanged::OpenOutputAtLaunchChanged(open_output) => {
+                self.profile_edit.set_open_output_at_launch(open_output);
+            }
+            JeProfileChanged::JavaExecutableChanged(java) => {
+                self.profile_edit.set_java_executable(if java.is_empty() { None } else { Some(java) });
+            }
             JeProfileChanged::Save => {
+                if let Some(Err(reason)) = self.profile_edit.additional_args().as_deref().map(split_shell_args) {
+                    let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Invalid JVM arguments").set_text(format!("The additional JVM arguments are malformed: {}", reason).as_str()).show_alert().inspect_err(|e| {
+                        eprintln!("Failed to show error dialog: {e}");
+                    });
+                    return;
+                }
+                if let Some(Err(reason)) = self.profile_edit.wrapper_command().map(split_shell_args) {
+                    let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Invalid wrapper command").set_text(format!("The wrapper command is malformed: {}", reason).as_str()).show_alert().inspect_err(|e| {
+                        eprintln!("Failed to show error dialog: {e}");
+                    });
+                    return;
+                }
+                // Without a target there is nowhere to go, so start on the title screen
+                if self.profile_edit.quick_play().is_some_and(|q| q.target().is_empty()) {
+                    self.profile_edit.set_quick_play(None);
+                }
+                if let Err(e) = sync_instance_dir(&mut self.profile_edit) {
+                    let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to set up instance folder").set_text(e.to_string().as_str()).show_alert().inspect_err(|e| {
+                        eprintln!("Failed to show error dialog: {e}");
+                    });
+                    return;
+                }
                 if let JavaEditionTab::EditProfile(id) = self.current_tab {
                     if let Some(id) = id {
                         let mut lock = PROFILES.write().unwrap();
@@ -170,6 +301,24 @@ impl JeGuiState {
                     }
                 }
             }
+            JeProfileChanged::Delete => {
+                let confirmed = MessageDialog::new().set_type(MessageType::Warning).set_title("Delete profile?").set_text(format!("Delete the profile {}?", self.profile_edit.name()).as_str()).show_confirm().unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+                // The saved profile knows the instance folder, the edited one might not have been saved yet
+                let Some(removed) = PROFILES.write().unwrap().remove_profile(self.profile_edit.id()) else { return };
+                if let Some(instance_dir) = removed.instance_dir().map(get_instance_dir).filter(|dir| dir.exists()) {
+                    let delete_folder = MessageDialog::new().set_type(MessageType::Warning).set_title("Delete instance folder?").set_text(format!("Also delete the instance folder {} with all of its mods, configs and saves?", instance_dir.display()).as_str()).show_confirm().unwrap_or(false);
+                    if delete_folder {
+                        let _ = delete_instance_dir(&removed).inspect_err(|e| {
+                            let _ = MessageDialog::new().set_type(MessageType::Error).set_title("Failed to delete instance folder").set_text(e.to_string().as_str()).show_alert();
+                        });
+                    }
+                }
+                self.selected_profile_id = PROFILES.read().unwrap().settings().selected_profile_id();
+                self.current_tab = JavaEditionTab::Installations;
+            }
             JeProfileChanged::IconChanged(i) => {
                 self.profile_edit.set_icon(i);
             }
@@ -186,6 +335,8 @@ impl JeGuiState {
     pub fn set_current_tab(&mut self, current_tab: JavaEditionTab) {
 
         if let JavaEditionTab::EditProfile(id) = current_tab {
+            // Probing every java takes a while, and runtimes may have been downloaded since the editor was last opened
+            WORKER_THREAD_HANDLE.lock().unwrap().as_ref().unwrap().send(WorkerThreadTask::DiscoverJavaInstallations).expect("Worker thread stopped");
 
             if let Some(id) = id {
                 let profiles = PROFILES.read().unwrap();
@@ -208,9 +359,30 @@ impl JeGuiState {
         }
     }
 
+    ///
+    /// The launch command is built from the saved profile, so unsaved changes would silently be left out of it
+    ///
+    fn require_saved_profile(&self) -> bool {
+        let is_saved = PROFILES.read().unwrap().find_profile(self.profile_edit.id()) == Some(&self.profile_edit);
+        if !is_saved {
+            let _ = MessageDialog::new().set_type(MessageType::Info).set_title("Unsaved changes").set_text("Save the installation first, so that way the launch command matches it.").show_alert().inspect_err(|e| {
+                eprintln!("Failed to show dialog: {e}");
+            });
+        }
+        is_saved
+    }
+
     pub fn selected_profile_id(&self) -> u128 {
         self.selected_profile_id
     }
+
+    pub fn last_game_output(&self) -> Option<&Arc<GameOutput>> {
+        self.last_game_output.as_ref()
+    }
+
+    pub fn set_last_game_output(&mut self, output: Arc<GameOutput>) {
+        self.last_game_output = Some(output);
+    }
 }
 
 fn validate_mod_loader_version(profile: &mut LauncherProfile) {
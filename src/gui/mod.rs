@@ -6,6 +6,7 @@ pub mod macros;
 pub mod settings;
 mod style;
 pub mod game_output;
+pub mod crash_report;
 //pub mod launcher;
 pub mod threading;
 
@@ -15,18 +16,26 @@ use crate::gui::je::{JavaEditionTab, JeGuiInteraction, JeGuiState};
 use crate::gui::macros::button_text;
 use crate::gui::settings::{on_message, SettingsGuiState, SettingsMessage};
 use crate::gui::style::{dark_container_style, generic_button_style, sidebar_container_style};
-use account::{AccountInteraction, AccountTabState};
+use account::{AccountInteraction, AccountMenu, AccountTabState};
 use iced::futures::StreamExt;
 use iced::widget::image::FilterMethod;
 use iced::widget::{button, column, container, image, row, text};
-use iced::{Element, Font, Length, Renderer, Size, Subscription, Task, Theme, window};
+use iced::{time, Element, Font, Length, Renderer, Size, Subscription, Task, Theme, window};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::panic::set_hook;
 use std::sync::atomic::AtomicBool;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use native_dialog::{MessageDialog, MessageType};
 use tokio::sync::mpsc::UnboundedReceiver;
 use crate::gui::threading::WorkerThread;
+use crate::gui::game_output::{game_output_window_settings, GameOutputGuiState, GameOutputMessage};
+use crate::gui::crash_report::{crash_report_window_settings, CrashReportGuiState, CrashReportMessage};
+use crate::launcher_rewrite::crash::GameExit;
+use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::game_output::GameOutput;
+use crate::launcher_rewrite::profiles::PROFILES;
 
 pub const MC_FONT: Font = Font::with_name("Minecraft");
 const MC_FONT_BYTES: &[u8] = include_bytes!("../../assets/minecraft_font.ttf");
@@ -46,6 +55,9 @@ pub struct LauncherGui {
     je_gui_state: JeGuiState,
     account_gui_state: AccountTabState,
     receiver: RefCell<Option<UnboundedReceiver<LauncherMessage>>>,
+    main_window: window::Id,
+    game_output_windows: HashMap<window::Id, GameOutputGuiState>,
+    crash_report_windows: HashMap<window::Id, CrashReportGuiState>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -67,6 +79,13 @@ pub enum GuiMessage {
     JavaEditionInteraction(JeGuiInteraction),
     AccountTabInteraction(AccountInteraction),
     SettingsTabInteraction(SettingsMessage),
+    GameLaunched { profile_id: u128, output: Arc<GameOutput> },
+    OpenGameOutput(Arc<GameOutput>),
+    GameOutputInteraction(window::Id, GameOutputMessage),
+    GameOutputTick,
+    GameExited { output: Arc<GameOutput>, exit: GameExit },
+    CrashReportInteraction(window::Id, CrashReportMessage),
+    WindowClosed(window::Id),
 }
 
 
@@ -84,19 +103,41 @@ impl Flags {
 impl LauncherGui {
 
     pub fn new(flags: Flags) -> (Self, Task<LauncherMessage>) {
+        let window_settings = window::Settings {
+            size: Size::new(1280_f32, 720_f32),
+            resizable: true,
+            decorations: true,
+            ..Default::default()
+        };
+        let (main_window, open_main_window) = window::open(window_settings);
         (
             Self {
                 selected_menu: Default::default(),
                 je_gui_state: Default::default(),
                 account_gui_state: Default::default(),
                 receiver: RefCell::new(Some(flags.receiver)),
+                main_window,
+                game_output_windows: HashMap::new(),
+                crash_report_windows: HashMap::new(),
             },
-            Task::none(),
+            open_main_window.discard(),
         )
     }
 
-    pub fn title(&self) -> String {
-        String::from("launcher-rs")
+    pub fn title(&self, window: window::Id) -> String {
+        if let Some(output_window) = self.game_output_windows.get(&window) {
+            return output_window.title();
+        }
+        match self.crash_report_windows.get(&window) {
+            Some(crash_window) => crash_window.title(),
+            None => String::from("launcher-rs"),
+        }
+    }
+
+    fn open_game_output(&mut self, output: Arc<GameOutput>) -> Task<LauncherMessage> {
+        let (id, open_window) = window::open(game_output_window_settings());
+        self.game_output_windows.insert(id, GameOutputGuiState::new(output));
+        open_window.discard()
     }
 
     pub fn update(&mut self, message: LauncherMessage) -> Task<LauncherMessage> {
@@ -110,6 +151,14 @@ impl LauncherGui {
             LauncherMessage::JavaEditionProfileChanged(val) => {
                 self.je_gui_state.je_profile_changed(val);
             }
+            LauncherMessage::JavaEditionInteraction(JeGuiInteraction::GameLaunchFailed(err)) if matches!(err.as_ref(), LauncherError::ReLoginRequired { .. }) => {
+                let log_in = MessageDialog::new().set_type(MessageType::Warning).set_title("Session expired").set_text(format!("{}\n\nLog in again now?", err).as_str()).show_confirm().unwrap_or(false);
+                if log_in {
+                    self.selected_menu = GameMenu::Accounts;
+                    self.account_gui_state.on_message(AccountInteraction::ChangeMenu(AccountMenu::login()));
+                    self.account_gui_state.on_message(AccountInteraction::LoginSubmit);
+                }
+            }
             LauncherMessage::JavaEditionInteraction(action) => {
                 self.je_gui_state.interact(action);
             }
@@ -121,13 +170,64 @@ impl LauncherGui {
                     on_message(s, action);
                 }
             }
-            _ => {}
+            LauncherMessage::GameLaunched { profile_id, output } => {
+                self.je_gui_state.set_last_game_output(output.clone());
+                let open_output = PROFILES.read().unwrap().find_profile(profile_id).is_some_and(|p| p.open_output_at_launch());
+                if open_output {
+                    return self.open_game_output(output);
+                }
+            }
+            LauncherMessage::OpenGameOutput(output) => {
+                return self.open_game_output(output);
+            }
+            LauncherMessage::GameOutputInteraction(id, action) => {
+                if let Some(output_window) = self.game_output_windows.get_mut(&id) {
+                    return output_window.update(action).map(move |m| LauncherMessage::GameOutputInteraction(id, m));
+                }
+            }
+            LauncherMessage::GameOutputTick => {
+                return Task::batch(self.game_output_windows.iter_mut().map(|(id, output_window)| {
+                    let id = *id;
+                    output_window.update(GameOutputMessage::OutputReceived).map(move |m| LauncherMessage::GameOutputInteraction(id, m))
+                }));
+            }
+            LauncherMessage::GameExited { output, exit } => {
+                if !exit.is_crash() {
+                    return Task::none();
+                }
+                let (id, open_window) = window::open(crash_report_window_settings());
+                self.crash_report_windows.insert(id, CrashReportGuiState::new(output, exit));
+                return open_window.discard();
+            }
+            LauncherMessage::CrashReportInteraction(id, action) => {
+                if let Some(crash_window) = self.crash_report_windows.get_mut(&id) {
+                    if let CrashReportMessage::ShowGameOutput = action {
+                        let output = crash_window.output().clone();
+                        return self.open_game_output(output);
+                    }
+                    crash_window.update(action);
+                }
+            }
+            LauncherMessage::WindowClosed(id) => {
+                if id == self.main_window {
+                    return iced::exit();
+                }
+                self.game_output_windows.remove(&id);
+                self.crash_report_windows.remove(&id);
+            }
         };
 
         Task::none()
     }
 
-    pub fn view(&self) -> Element<'_, LauncherMessage, LauncherTheme, LauncherRenderer> {
+    pub fn view(&self, window: window::Id) -> Element<'_, LauncherMessage, LauncherTheme, LauncherRenderer> {
+        if let Some(output_window) = self.game_output_windows.get(&window) {
+            return output_window.view().map(move |m| LauncherMessage::GameOutputInteraction(window, m));
+        }
+        if let Some(crash_window) = self.crash_report_windows.get(&window) {
+            return crash_window.view().map(move |m| LauncherMessage::CrashReportInteraction(window, m));
+        }
+
         let sidebar = container(column![self.sidebar_accounts_button(), row![].height(40), self.sidebar_je_button(), self.sidebar_je_server_button(), self.sidebar_be_button(), self.sidebar_be_server_button(), row![].height(Length::Fill), self.sidebar_settings_button(),].height(Length::Fill).width(Length::Fill))
             .height(Length::Fill)
             .width(270)
@@ -160,13 +260,19 @@ impl LauncherGui {
 
     pub fn subscription(&self) -> Subscription<LauncherMessage> {
         //Subscription::run_with_id("led changes", self.receiver.borrow_mut())
-        Subscription::run_with_id("led changes", futures_util::stream::unfold(
+        let worker_messages = Subscription::run_with_id("led changes", futures_util::stream::unfold(
             self.receiver.take(),
             move |mut receiver| async move {
                 let m = receiver.as_mut().unwrap().recv().await.unwrap();
                 Some((m, receiver))
             })
-        )
+        );
+        let output_tick = if self.game_output_windows.is_empty() {
+            Subscription::none()
+        } else {
+            time::every(Duration::from_millis(100)).map(|_| LauncherMessage::GameOutputTick)
+        };
+        Subscription::batch([worker_messages, output_tick, window::close_events().map(LauncherMessage::WindowClosed)])
     }
 
     fn sidebar_accounts_button(&self) -> Element<'static, LauncherMessage, LauncherTheme, LauncherRenderer> {
@@ -227,15 +333,7 @@ pub(crate) fn gui_main() {
     lock.replace(WorkerThread::new(message_send));
     drop(lock);
 
-    let window_settings = window::Settings {
-        size: Size::new(1280_f32, 720_f32),
-        resizable: true,
-        decorations: true,
-        ..Default::default()
-    };
-
-    iced::application(LauncherGui::title, LauncherGui::update, LauncherGui::view)
-        .window(window_settings)
+    iced::daemon(LauncherGui::title, LauncherGui::update, LauncherGui::view)
         .font(MC_FONT_BYTES)
         .default_font(MC_FONT)
         .subscription(LauncherGui::subscription)
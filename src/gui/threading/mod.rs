@@ -1,14 +1,6 @@
-use crate::launcher_rewrite::authentication::account_data::{LoggedInAccount, MicrosoftTokenInfo, MinecraftAccountInfo, MinecraftTokenInfo, XboxLiveTokenInfo};
 use crate::gui::account::AccountInteraction;
 use crate::gui::LauncherMessage;
 use crate::util::StripCanonicalization;
-use crate::launcher_rewrite::installer::APP_USER_AGENT;
-use regex::Regex;
-use reqwest::blocking::Client;
-use reqwest::blocking::ClientBuilder;
-use reqwest::redirect::Policy;
-use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
-use std::collections::HashMap;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -20,19 +12,24 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::gui::GuiMessage::JavaEditionInteraction;
 use crate::gui::je::JeGuiInteraction;
 use crate::launcher_rewrite::authentication::LOGGED_IN_ACCOUNT_DATA;
-use crate::launcher_rewrite::authentication::login::{login, LoginState};
+use crate::launcher_rewrite::authentication::login::login_with_device_code;
 use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::game_output::GameOutput;
 use crate::launcher_rewrite::installer::Downloadable;
-use crate::launcher_rewrite::{launch_game, launch_game_from_profile};
+use crate::launcher_rewrite::java::discovery::discover_java_installations;
+use crate::launcher_rewrite::{get_launch_command_from_profile, launch_game, launch_game_from_profile};
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::profiles::PROFILES;
 
 pub enum WorkerThreadTask {
     LaunchGame(u128),
+    // Resolves the launch command of a profile, and writes it to a script if a path is given
+    ExportLaunchCommand { profile_id: u128, script_path: Option<PathBuf>, show_token: bool },
     DownloadVersionManifest,
     LoadProfiles,
     Shutdown,
-    MicrosoftLogin { username: String, password: String },
+    MicrosoftLogin,
+    DiscoverJavaInstallations,
 }
 
 pub struct WorkerThread {
@@ -72,8 +69,10 @@ fn worker_thread(comms: Receiver<WorkerThreadTask>, message_send: UnboundedSende
                 match v {
                     WorkerThreadTask::LaunchGame(profile_id) => {
                         match launch_game_from_profile(profile_id) {
-                            Ok(()) => {
+                            Ok(instance) => {
                                 // Party!!!!!
+                                message_send.send(LauncherMessage::GameLaunched { profile_id, output: instance.output().clone() }).unwrap();
+                                watch_for_exit(instance.output().clone(), message_send.clone());
                             }
                             Err(e) => {
                                 // Pass the error back to the gui, so it can be displayed
@@ -81,107 +80,37 @@ fn worker_thread(comms: Receiver<WorkerThreadTask>, message_send: UnboundedSende
                             }
                         };
                     }
+                    WorkerThreadTask::ExportLaunchCommand { profile_id, script_path, show_token } => {
+                        let exported = get_launch_command_from_profile(profile_id).and_then(|command| {
+                            if let Some(path) = &script_path {
+                                command.write_shell_script(path, show_token)?;
+                            }
+                            Ok(command.to_display_string(show_token))
+                        });
+                        message_send.send(JavaEditionInteraction(match exported {
+                            Ok(command) => JeGuiInteraction::LaunchCommandExported { command, script_path },
+                            Err(e) => JeGuiInteraction::LaunchCommandFailed(Arc::new(e)),
+                        })).unwrap();
+                    }
+                    WorkerThreadTask::DiscoverJavaInstallations => {
+                        message_send.send(JavaEditionInteraction(JeGuiInteraction::JavaInstallationsDiscovered(discover_java_installations()))).unwrap();
+                    }
                     WorkerThreadTask::DownloadVersionManifest => {}
                     WorkerThreadTask::LoadProfiles => {}
                     WorkerThreadTask::Shutdown => break 'events,
-                    WorkerThreadTask::MicrosoftLogin { username, password } => {
-                        message_send.send(match login(username, password) {
-                            Ok(l) => match l {
-                                    LoginState::Requires2FA(c) => LauncherMessage::AccountTabInteraction(AccountInteraction::_2FARequired),
-                                    LoginState::InvalidCredentials => LauncherMessage::AccountTabInteraction(AccountInteraction::InvalidCreds),
-                                    LoginState::LoggedIn => LauncherMessage::AccountTabInteraction(AccountInteraction::LoginSuccess),
-                                },
-                            Err(e) => JavaEditionInteraction(JeGuiInteraction::GameLaunchFailed(Arc::new(e))),
-                        }).unwrap();
-
-                        /*
-                        let cookies = Arc::new(CookieStoreMutex::new(CookieStore::default()));
-
-                        let client = ClientBuilder::new().user_agent(APP_USER_AGENT).redirect(Policy::default()).cookie_provider(cookies).build().unwrap();
-
-                        let response = client.get("https://login.live.com/oauth20_authorize.srf?client_id=000000004C12AE6F&redirect_uri=https://login.live.com/oauth20_desktop.srf&scope=service::user.auth.xboxlive.com::MBI_SSL&display=touch&response_type=token&locale=en").send().unwrap().text().unwrap();
-                        //println!("\n\n\n\nResponse 1: {}\n\n\n\n", response);
-
-                        let sft_tag_regex = Regex::new("value=\"(.+?)\"").unwrap();
-                        let sft_tag = sft_tag_regex.find(response.as_str()).unwrap().as_str().strip_suffix("\"").unwrap().strip_prefix("value=\"").unwrap();
-                        //println!("Sft tag: {}", sft_tag);
-
-                        let ulr_post_regex = Regex::new("urlPost:'(.+?)'").unwrap();
-                        let url_post = ulr_post_regex.find(response.as_str()).unwrap().as_str().strip_suffix("'").unwrap().strip_prefix("urlPost:'").unwrap();
-                        //println!("Url Post: {}", url_post);
-
-                        let url_encoded_username = urlencoding::encode(username.as_str());
-                        let url_encoded_password = urlencoding::encode(password.as_str());
-                        let url_encoded_sft_tag = urlencoding::encode(sft_tag);
-
-                        let body = format!("login={}&loginfmt={}&passwd={}&PPFT={}", url_encoded_username, url_encoded_username, url_encoded_password, url_encoded_sft_tag);
-
-                        let response = client.post(url_post).body(body).header("Content-Type", "application/x-www-form-urlencoded").send().unwrap();
-                        let final_url: String = response.url().as_str().into();
-                        let response = response.text().unwrap();
-
-                        //println!("\n\n\n\nResponse: {}\n\n\n\n", response);
-                        //println!("final url: {}", final_url);
-
-                        if response.contains("Sign in to") {
-                            // Wrong Credentials
-                            println!("Wrong Creds!");
-                            message_send.send(LauncherMessage::AccountTabInteraction(AccountInteraction::InvalidCreds)).unwrap();
-                            break 'login;
-                        }
-
-                        if response.contains("Help us protect your account") {
-                            // 2FA
-                            println!("2FA Required");
-                            message_send.send(LauncherMessage::AccountTabInteraction(AccountInteraction::_2FARequired)).unwrap();
-                            break 'login;
-                        }
-
-                        let raw_login_data = final_url.split_once("#").unwrap().1;
-
-                        let data_pairs: HashMap<&str, &str> = raw_login_data.split("&").map(|i| i.split_once("=").unwrap()).collect();
-
-                        let ms_token_info = MicrosoftTokenInfo::from_map(data_pairs);
-
-                        // Sign in to Xbox Live
-
-                        let body = format!("{{\"Properties\": {{\"AuthMethod\": \"RPS\",\"SiteName\": \"user.auth.xboxlive.com\",\"RpsTicket\": \"{}\"}},\"RelyingParty\": \"http://auth.xboxlive.com\",\"TokenType\": \"JWT\"}}", ms_token_info.access_token());
-
-                        let response = client.post("https://user.auth.xboxlive.com/user/authenticate").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send().unwrap().text().unwrap();
-
-                        let xbox_token_info: XboxLiveTokenInfo = serde_json::from_str(response.as_str()).unwrap();
-
-                        // get XSTS token
-
-                        let body = format!("{{\"Properties\": {{\"SandboxId\": \"RETAIL\",\"UserTokens\": [\"{}\"]}},\"RelyingParty\": \"rp://api.minecraftservices.com/\",\"TokenType\": \"JWT\"}}", xbox_token_info.token());
-
-                        let response = client.post("https://xsts.auth.xboxlive.com/xsts/authorize").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send().unwrap().text().unwrap();
-
-                        //println!("Xsts Response: {}", response.as_str());
-
-                        let xsts_token_info: XboxLiveTokenInfo = serde_json::from_str(response.as_str()).unwrap();
-
-                        // get Minecraft Token
-
-                        let body = format!("{{\"identityToken\" : \"XBL3.0 x={};{}\",\"ensureLegacyEnabled\" : true}}", xsts_token_info.iter().next().unwrap().as_str(), xsts_token_info.token());
-
-                        let response = client.post("https://api.minecraftservices.com/authentication/login_with_xbox").header("Content-Type", "application/json").body(body).send().unwrap().text().unwrap();
-
-                        let minecraft_token_info: MinecraftTokenInfo = serde_json::from_str(response.as_str()).unwrap();
-
-                        //println!("Minecraft Token: {}", minecraft_token_info.access_token());
-
-                        let response = client.get("https://api.minecraftservices.com/minecraft/profile").header("Authorization", format!("Bearer {}", minecraft_token_info.access_token())).send().unwrap().text().unwrap();
-
-                        //println!("Account Info: {}", &response);
-
-                        let profile_info: MinecraftAccountInfo = serde_json::from_str(response.as_str()).unwrap();
-
-                        let complete_token_info = LoggedInAccount::new(ms_token_info, xbox_token_info, xsts_token_info, minecraft_token_info, profile_info);
-
-                        LOGGED_IN_ACCOUNT_DATA.write().unwrap().add_account_and_set_active(complete_token_info);
-
-                        message_send.send(LauncherMessage::AccountTabInteraction(AccountInteraction::LoginSuccess)).unwrap();*/
+                    WorkerThreadTask::MicrosoftLogin => {
+                        // Waiting for the user to sign in can take minutes, which shouldn't block launching the game
+                        let message_send = message_send.clone();
+                        let _ = thread::Builder::new().name("Login Thread".into()).spawn(move || {
+                            let code_send = message_send.clone();
+                            let result = login_with_device_code(|code| {
+                                let _ = code_send.send(LauncherMessage::AccountTabInteraction(AccountInteraction::DeviceCodeReceived { user_code: code.user_code().to_owned(), verification_uri: code.verification_uri().to_owned() }));
+                            });
+                            let _ = message_send.send(LauncherMessage::AccountTabInteraction(match result {
+                                Ok(()) => AccountInteraction::LoginSuccess,
+                                Err(e) => AccountInteraction::LoginError(Arc::new(e)),
+                            }));
+                        });
                     }
                 }
             }
@@ -194,3 +123,13 @@ fn worker_thread(comms: Receiver<WorkerThreadTask>, message_send: UnboundedSende
         }
     }
 }
+
+///
+/// Waits for the game to exit on its own thread, and tells the gui how it went
+///
+fn watch_for_exit(output: Arc<GameOutput>, message_send: UnboundedSender<LauncherMessage>) {
+    let _ = thread::Builder::new().name("Game Exit Watcher Thread".into()).spawn(move || {
+        let exit = output.wait_for_exit();
+        let _ = message_send.send(LauncherMessage::GameExited { output, exit });
+    });
+}
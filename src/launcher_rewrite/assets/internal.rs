@@ -6,6 +6,12 @@ use serde::{Deserialize, Serialize};
 pub (in crate::launcher_rewrite::assets) struct AssetsIndexJson<'file> {
     #[serde(borrow)]
     pub objects: HashMap<&'file str, ObjectInfo<'file>>,
+    // Set by the `legacy` index, whose versions read assets from `assets/virtual/legacy`
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    // Set by the `pre-1.6` index, whose versions read assets from the `resources` folder of the game directory
+    #[serde(default)]
+    pub map_to_resources: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
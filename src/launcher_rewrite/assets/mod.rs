@@ -1,9 +1,10 @@
+use std::fs;
 use std::num::NonZeroU64;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use reqwest::Url;
 use serde::{Deserialize, Deserializer};
 use serde::de::Error;
-use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
 use crate::launcher_rewrite::installer::Downloadable;
 use crate::launcher_rewrite::path_handler::get_objects_dir;
 use crate::launcher_rewrite::util::hash::{FileHash, Sha1, sha1_from_base64_str};
@@ -11,10 +12,14 @@ use crate::launcher_rewrite::util::hash::{FileHash, Sha1, sha1_from_base64_str};
 mod internal;
 
 pub const ASSETS_URL: &'static str = "https://resources.download.minecraft.net/";
+// Inside the game directory
+pub const RESOURCES_FOLDER: &'static str = "resources";
 
 #[derive(Clone)]
 pub struct AssetsIndex<'file> {
     objects: Vec<Object<'file>>,
+    is_virtual: bool,
+    map_to_resources: bool,
 }
 
 impl<'file> AssetsIndex<'file> {
@@ -22,6 +27,38 @@ impl<'file> AssetsIndex<'file> {
         self.objects.iter().map(|o| o.download(version_name)).collect::<Result<Vec<()>, LauncherError>>()?;
         Ok(())
     }
+
+    pub fn is_virtual(&self) -> bool {
+        self.is_virtual
+    }
+
+    pub fn map_to_resources(&self) -> bool {
+        self.map_to_resources
+    }
+
+    ///
+    /// Puts every downloaded object into `dir` under its actual name, for old versions that can't read the hashed objects folder.
+    /// Objects that are already there are skipped.
+    ///
+    pub fn copy_objects_to(&self, dir: &Path) -> LauncherResult<()> {
+        for object in &self.objects {
+            let mut target = dir.to_owned();
+            target.extend(object.name.split('/'));
+            if target.metadata().is_ok_and(|m| object.size.is_none_or(|size| m.len() == size.get())) {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let _ = fs::remove_file(&target);
+            let source = object.get_file_path("");
+            // Hard links save a few hundred megabytes, but don't work across drives
+            if fs::hard_link(&source, &target).is_err() {
+                fs::copy(&source, &target)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<'de: 'file, 'file> Deserialize<'de> for AssetsIndex<'file> {
@@ -34,6 +71,8 @@ impl<'de: 'file, 'file> Deserialize<'de> for AssetsIndex<'file> {
         let objects= objects?;
         Ok(Self {
             objects,
+            is_virtual: internal.is_virtual,
+            map_to_resources: internal.map_to_resources,
         })
     }
 }
@@ -77,4 +116,18 @@ impl<'file> Downloadable for Object<'file> {
     fn get_size(&self) -> Option<NonZeroU64> {
         self.size
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::launcher_rewrite::assets::AssetsIndex;
+
+    #[test]
+    fn reads_legacy_layouts() {
+        let legacy: AssetsIndex = serde_json::from_str(r#"{"virtual": true, "objects": {"sounds/random/click.ogg": {"hash": "4b6ad9a6d2b2a7e2a9b4c5bd5fbe3c6b0c1d1e1f", "size": 1}}}"#).unwrap();
+        assert!(legacy.is_virtual() && !legacy.map_to_resources());
+        let pre_1_6: AssetsIndex = serde_json::from_str(r#"{"map_to_resources": true, "objects": {}}"#).unwrap();
+        assert!(pre_1_6.map_to_resources() && !pre_1_6.is_virtual());
+        let modern: AssetsIndex = serde_json::from_str(r#"{"objects": {}}"#).unwrap();
+        assert!(!modern.is_virtual() && !modern.map_to_resources());
+    }
+}
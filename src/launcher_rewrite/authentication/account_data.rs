@@ -1,6 +1,6 @@
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::fs;
 use std::ops::Deref;
 use std::path::Path;
@@ -73,6 +73,15 @@ impl AccountData {
         }
     }
 
+    ///
+    /// Swaps in the account with refreshed tokens, keeping its position and whether it's active
+    ///
+    pub fn replace_account(&mut self, uuid: &str, acc: LoggedInAccount) {
+        if let Some(index) = self.index_of(uuid) {
+            self.logged_in_accounts[index] = acc;
+        }
+    }
+
     pub fn set_active_by_uuid(&mut self, uuid: &str) {
         if let Some(index) = self.index_of(uuid) {
             self.active_account_id = Some(index);
@@ -88,6 +97,9 @@ impl Deref for AccountData {
     }
 }
 
+// Tokens are refreshed before launching once they expire within this time, so that way they last through a play session
+const TOKEN_REFRESH_MARGIN: TimeDelta = TimeDelta::hours(1);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LoggedInAccount {
     microsoft_token_info: MicrosoftTokenInfo,
@@ -96,20 +108,51 @@ pub struct LoggedInAccount {
     minecraft_token_info: MinecraftTokenInfo,
     #[serde(flatten)]
     minecraft_account_info: MinecraftAccountInfo,
+    // Accounts saved before entitlements were checked are assumed to own the game
+    #[serde(default = "owns_game_default")]
+    owns_game: bool,
+    // When the tokens were issued. Accounts saved before this was tracked are refreshed on the next launch.
+    #[serde(default)]
+    authenticated_at: DateTime<Utc>,
+}
+
+const fn owns_game_default() -> bool {
+    true
 }
 
 impl LoggedInAccount {
     #[inline(always)]
-    pub fn new(microsoft_token_info: MicrosoftTokenInfo, xbox_live_token_info: XboxLiveTokenInfo, xsts_token_info: XboxLiveTokenInfo, minecraft_token_info: MinecraftTokenInfo, minecraft_account_info: MinecraftAccountInfo) -> Self {
+    pub fn new(microsoft_token_info: MicrosoftTokenInfo, xbox_live_token_info: XboxLiveTokenInfo, xsts_token_info: XboxLiveTokenInfo, minecraft_token_info: MinecraftTokenInfo, minecraft_account_info: MinecraftAccountInfo, owns_game: bool) -> Self {
         Self {
             microsoft_token_info,
             xbox_live_token_info,
             xsts_token_info,
             minecraft_token_info,
             minecraft_account_info,
+            owns_game,
+            authenticated_at: Utc::now(),
         }
     }
 
+    pub fn minecraft_token_expires_at(&self) -> DateTime<Utc> {
+        self.authenticated_at + TimeDelta::seconds(self.minecraft_token_info.expires_in as i64)
+    }
+
+    ///
+    /// Whether the Minecraft token has expired or is about to
+    ///
+    pub fn needs_refresh(&self) -> bool {
+        Utc::now() + TOKEN_REFRESH_MARGIN >= self.minecraft_token_expires_at()
+    }
+
+    pub fn owns_game(&self) -> bool {
+        self.owns_game
+    }
+
+    pub fn is_demo_user(&self) -> bool {
+        !self.owns_game
+    }
+
     pub fn microsoft_token_info(&self) -> &MicrosoftTokenInfo {
         &self.microsoft_token_info
     }
@@ -133,6 +176,24 @@ impl LoggedInAccount {
     pub fn minecraft_token(&self) -> &str {
         &self.minecraft_token_info.access_token
     }
+
+    ///
+    /// The Xbox user id from the XSTS display claims. Only present if the XSTS token was issued with the `xid` claim.
+    ///
+    pub fn xuid(&self) -> Option<&str> {
+        self.xsts_token_info.iter().find_map(|claim| claim.xid())
+    }
+
+    pub fn user_hash(&self) -> Option<&str> {
+        self.xsts_token_info.first().map(|claim| claim.uhs())
+    }
+
+    ///
+    /// Every account is logged in through Microsoft, legacy Mojang accounts can't log in anymore
+    ///
+    pub fn user_type(&self) -> &'static str {
+        "msa"
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -142,28 +203,12 @@ pub struct MicrosoftTokenInfo {
     expires_in: u64,
     scope: String,
     refresh_token: String,
+    // Only sent by the live.com endpoints
+    #[serde(default)]
     user_id: String,
 }
 
 impl MicrosoftTokenInfo {
-    pub fn from_map(map: HashMap<&str, &str>) -> Self {
-        let access_token = *map.get("access_token").unwrap();
-        let token_type = *map.get("token_type").unwrap();
-        let expires_in: u64 = map.get("expires_in").unwrap().parse::<u64>().unwrap();
-        let scope = *map.get("scope").unwrap();
-        let refresh_token = *map.get("refresh_token").unwrap();
-        let user_id = *map.get("user_id").unwrap();
-
-        Self {
-            access_token: access_token.into(),
-            token_type: token_type.into(),
-            expires_in,
-            scope: scope.into(),
-            refresh_token: refresh_token.into(),
-            user_id: user_id.into(),
-        }
-    }
-
     pub fn access_token(&self) -> &str {
         &self.access_token
     }
@@ -236,6 +281,19 @@ impl Deref for DisplayClaims {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserHash {
     uhs: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    xid: Option<String>,
+}
+
+impl UserHash {
+    pub fn uhs(&self) -> &str {
+        &self.uhs
+    }
+
+    pub fn xid(&self) -> Option<&str> {
+        self.xid.as_deref()
+    }
 }
 
 impl Deref for UserHash {
@@ -281,6 +339,18 @@ pub struct MinecraftAccountInfo {
 }
 
 impl MinecraftAccountInfo {
+    ///
+    /// Accounts that don't own the game don't have a profile, so make up one to play the demo with
+    ///
+    pub fn demo(id: String) -> Self {
+        Self {
+            name: String::from("Player"),
+            id,
+            skins: Vec::new(),
+            capes: Vec::new(),
+        }
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -366,3 +436,36 @@ pub fn get_account_data() -> AccountData {
     #[cfg(not(debug_assertions))]
     return load_from_file(TOKENS_FILE_PATH.as_path(), false);
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use crate::launcher_rewrite::authentication::account_data::LoggedInAccount;
+
+    fn account_json(minecraft_expires_in: u64, authenticated_at: Option<String>) -> String {
+        let authenticated_at = authenticated_at.map(|at| format!(r#", "authenticated_at": "{at}""#)).unwrap_or_default();
+        format!(r#"{{
+            "microsoft_token_info": {{"access_token": "ms", "token_type": "bearer", "expires_in": 86400, "scope": "scope", "refresh_token": "refresh"}},
+            "xbox_live_token_info": {{"IssueInstant": "", "NotAfter": "", "Token": "xbox", "DisplayClaims": {{"xui": [{{"uhs": "hash"}}]}}}},
+            "xsts_token_info": {{"IssueInstant": "", "NotAfter": "", "Token": "xsts", "DisplayClaims": {{"xui": [{{"uhs": "hash"}}]}}}},
+            "minecraft_token_info": {{"username": "user", "access_token": "token", "token_type": "Bearer", "expires_in": {minecraft_expires_in}}},
+            "name": "Player", "id": "uuid", "skins": [], "capes": []{authenticated_at}
+        }}"#)
+    }
+
+    #[test]
+    fn needs_refresh_near_expiry() {
+        // Saved before authenticated_at was tracked, so it counts as issued at the epoch
+        let legacy: LoggedInAccount = serde_json::from_str(&account_json(86400, None)).unwrap();
+        assert!(legacy.needs_refresh());
+
+        let fresh: LoggedInAccount = serde_json::from_str(&account_json(86400, Some(Utc::now().to_rfc3339()))).unwrap();
+        assert!(!fresh.needs_refresh());
+
+        let expiring: LoggedInAccount = serde_json::from_str(&account_json(1800, Some(Utc::now().to_rfc3339()))).unwrap();
+        assert!(expiring.needs_refresh());
+
+        let expired: LoggedInAccount = serde_json::from_str(&account_json(86400, Some((Utc::now() - TimeDelta::days(2)).to_rfc3339()))).unwrap();
+        assert!(expired.needs_refresh());
+    }
+}
@@ -1,84 +1,200 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-use regex::Regex;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 use reqwest::blocking;
 use reqwest::blocking::ClientBuilder;
-use reqwest::redirect::Policy;
-use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
+use reqwest::StatusCode;
+use serde::Deserialize;
 use crate::launcher_rewrite::authentication::account_data::{LoggedInAccount, MicrosoftTokenInfo, MinecraftAccountInfo, MinecraftTokenInfo, XboxLiveTokenInfo};
 use crate::launcher_rewrite::authentication::LOGGED_IN_ACCOUNT_DATA;
-use crate::launcher_rewrite::authentication::login::LoginState::{InvalidCredentials, LoggedIn, Requires2FA};
-use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
 use crate::launcher_rewrite::installer::APP_USER_AGENT;
 
+const DEVICE_CODE_URL: &str = "https://login.live.com/oauth20_connect.srf";
+const TOKEN_URL: &str = "https://login.live.com/oauth20_token.srf";
+// A client id that is allowed to use the device code flow and sign in to Xbox Live
+const CLIENT_ID: &str = "00000000441cc96b";
+const SCOPE: &str = "service::user.auth.xboxlive.com::MBI_SSL";
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const REFRESH_TOKEN_GRANT_TYPE: &str = "refresh_token";
+
+// Added to the polling interval every time the server asks us to slow down
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
+///
+/// The endpoints and client used to get a Microsoft token. Only differs from the default in tests, which run against a mock server.
+///
 #[derive(Debug, Clone)]
-#[must_use]
-pub enum LoginState {
-    Requires2FA(blocking::Client),
-    InvalidCredentials,
-    LoggedIn,
+pub struct MicrosoftOAuth {
+    device_code_url: String,
+    token_url: String,
+    client_id: String,
+    scope: String,
 }
 
-pub fn login(username: String, password: String) -> Result<LoginState, LauncherError> {
-    let cookies = Arc::new(CookieStoreMutex::new(CookieStore::default()));
+impl Default for MicrosoftOAuth {
+    fn default() -> Self {
+        Self::new(DEVICE_CODE_URL.to_owned(), TOKEN_URL.to_owned(), CLIENT_ID.to_owned(), SCOPE.to_owned())
+    }
+}
 
-    let client = ClientBuilder::new().user_agent(APP_USER_AGENT).redirect(Policy::default()).cookie_provider(cookies).build().unwrap();
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeviceCode {
+    user_code: String,
+    device_code: String,
+    verification_uri: String,
+    // Both in seconds
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
 
-    let response = client.get("https://login.live.com/oauth20_authorize.srf?client_id=000000004C12AE6F&redirect_uri=https://login.live.com/oauth20_desktop.srf&scope=service::user.auth.xboxlive.com::MBI_SSL&display=touch&response_type=token&locale=en").send()?.text()?;
-    //println!("\n\n\n\nResponse 1: {}\n\n\n\n", response);
+const fn default_interval() -> u64 {
+    5
+}
 
-    let sft_tag_regex = Regex::new("value=\"(.+?)\"").unwrap();
-    let sft_tag = sft_tag_regex.find(response.as_str()).unwrap().as_str().strip_suffix("\"").unwrap().strip_prefix("value=\"").unwrap();
-    //println!("Sft tag: {}", sft_tag);
+impl DeviceCode {
+    pub fn user_code(&self) -> &str {
+        &self.user_code
+    }
 
-    let ulr_post_regex = Regex::new("urlPost:'(.+?)'").unwrap();
-    let url_post = ulr_post_regex.find(response.as_str()).unwrap().as_str().strip_suffix("'").unwrap().strip_prefix("urlPost:'").unwrap();
-    //println!("Url Post: {}", url_post);
+    pub fn verification_uri(&self) -> &str {
+        &self.verification_uri
+    }
 
-    let url_encoded_username = urlencoding::encode(username.as_str());
-    let url_encoded_password = urlencoding::encode(password.as_str());
-    let url_encoded_sft_tag = urlencoding::encode(sft_tag);
+    pub fn expires_in(&self) -> u64 {
+        self.expires_in
+    }
+}
 
-    let body = format!("login={}&loginfmt={}&passwd={}&PPFT={}", url_encoded_username, url_encoded_username, url_encoded_password, url_encoded_sft_tag);
+#[derive(Deserialize, Debug, Clone)]
+struct OAuthErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
 
-    let response = client.post(url_post).body(body).header("Content-Type", "application/x-www-form-urlencoded").send()?;
-    let final_url: String = response.url().as_str().into();
-    let response = response.text().unwrap();
+impl MicrosoftOAuth {
+    pub fn new(device_code_url: String, token_url: String, client_id: String, scope: String) -> Self {
+        Self { device_code_url, token_url, client_id, scope }
+    }
 
-    //println!("\n\n\n\nResponse: {}\n\n\n\n", response);
-    //println!("final url: {}", final_url);
+    ///
+    /// Starts a device code login. The user has to enter the returned code at its verification uri, while `poll_for_token` waits for that to happen.
+    ///
+    pub fn request_device_code(&self, client: &blocking::Client) -> LauncherResult<DeviceCode> {
+        let form = [("client_id", self.client_id.as_str()), ("scope", self.scope.as_str()), ("response_type", "device_code")];
+        let response = client.post(&self.device_code_url).form(&form).send()?.text()?;
+        parse_oauth_response(&response)
+    }
 
-    if response.contains("Sign in to") {
-        // Wrong Credentials
-        println!("Wrong Creds!");
-        return Ok(InvalidCredentials);
+    ///
+    /// Blocks until the user finished signing in, declined, or the code expired
+    ///
+    pub fn poll_for_token(&self, client: &blocking::Client, device_code: &DeviceCode) -> LauncherResult<MicrosoftTokenInfo> {
+        let form = [("client_id", self.client_id.as_str()), ("grant_type", DEVICE_CODE_GRANT_TYPE), ("device_code", device_code.device_code.as_str())];
+        let deadline = Instant::now() + Duration::from_secs(device_code.expires_in);
+        let mut interval = Duration::from_secs(device_code.interval);
+
+        loop {
+            sleep(interval);
+            if Instant::now() >= deadline {
+                return Err(LauncherError::LoginExpired);
+            }
+            let response = client.post(&self.token_url).form(&form).send()?.text()?;
+            match parse_oauth_response::<MicrosoftTokenInfo>(&response) {
+                Err(LauncherError::OAuthError { error, .. }) if error == "authorization_pending" => {}
+                Err(LauncherError::OAuthError { error, .. }) if error == "slow_down" => interval += SLOW_DOWN_INCREMENT,
+                Err(LauncherError::OAuthError { error, .. }) if error == "authorization_declined" => return Err(LauncherError::LoginDeclined),
+                Err(LauncherError::OAuthError { error, .. }) if error == "expired_token" => return Err(LauncherError::LoginExpired),
+                other => return other,
+            }
+        }
     }
+}
+
+impl MicrosoftOAuth {
+    ///
+    /// Gets a new Microsoft token with the refresh token of an earlier login
+    ///
+    pub fn refresh(&self, client: &blocking::Client, refresh_token: &str) -> LauncherResult<MicrosoftTokenInfo> {
+        let form = [("client_id", self.client_id.as_str()), ("scope", self.scope.as_str()), ("grant_type", REFRESH_TOKEN_GRANT_TYPE), ("refresh_token", refresh_token)];
+        let response = fail_on_transient_status(client.post(&self.token_url).form(&form).send()?)?.text()?;
+        parse_oauth_response(&response)
+    }
+}
+
+///
+/// Turns rate limiting and server errors into an error, but leaves other error responses alone since their body says what went wrong
+///
+fn fail_on_transient_status(response: blocking::Response) -> reqwest::Result<blocking::Response> {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        response.error_for_status()
+    } else {
+        Ok(response)
+    }
+}
 
-    if response.contains("Help us protect your account") {
-        // 2FA
-        println!("2FA Required");
-        return Ok(Requires2FA(client))
+///
+/// Whether logging in failed because of the network or the servers, rather than the tokens, so that way it's worth trying again later
+///
+pub fn is_transient_error(error: &LauncherError) -> bool {
+    match error {
+        LauncherError::DownloadError(e) => e.status().is_none_or(|status| status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()),
+        _ => false,
     }
+}
 
-    let raw_login_data = final_url.split_once("#").unwrap().1;
+fn parse_oauth_response<T: for<'a> Deserialize<'a>>(response: &str) -> LauncherResult<T> {
+    if let Ok(error) = serde_json::from_str::<OAuthErrorResponse>(response) {
+        return Err(LauncherError::OAuthError { error: error.error, description: error.error_description });
+    }
+    Ok(serde_json::from_str(response)?)
+}
 
-    let data_pairs: HashMap<&str, &str> = raw_login_data.split("&").map(|i| i.split_once("=").unwrap()).collect();
+pub fn login_client() -> blocking::Client {
+    ClientBuilder::new().user_agent(APP_USER_AGENT).build().unwrap()
+}
 
-    let ms_token_info = MicrosoftTokenInfo::from_map(data_pairs);
+///
+/// Logs in through the device code flow and makes the account the active one. `show_code` is called as soon as the user can enter the code.
+///
+pub fn login_with_device_code<F: FnOnce(&DeviceCode)>(show_code: F) -> LauncherResult<()> {
+    let client = login_client();
+    let oauth = MicrosoftOAuth::default();
+    let device_code = oauth.request_device_code(&client)?;
+    show_code(&device_code);
+    let ms_token_info = oauth.poll_for_token(&client, &device_code)?;
+    let account = authenticate_minecraft(&client, ms_token_info)?;
+    LOGGED_IN_ACCOUNT_DATA.write().unwrap().add_account_and_set_active(account);
+    Ok(())
+}
 
+///
+/// Logs in again with the refresh token of the account, without the user having to do anything
+///
+pub fn refresh_account(account: &LoggedInAccount) -> LauncherResult<LoggedInAccount> {
+    let client = login_client();
+    let ms_token_info = MicrosoftOAuth::default().refresh(&client, account.microsoft_token_info().refresh_token())?;
+    authenticate_minecraft(&client, ms_token_info)
+}
+
+///
+/// Exchanges a Microsoft token for the Xbox Live, XSTS and Minecraft tokens and looks up the Minecraft profile
+///
+pub fn authenticate_minecraft(client: &blocking::Client, ms_token_info: MicrosoftTokenInfo) -> LauncherResult<LoggedInAccount> {
     // Sign in to Xbox Live
 
     let body = format!("{{\"Properties\": {{\"AuthMethod\": \"RPS\",\"SiteName\": \"user.auth.xboxlive.com\",\"RpsTicket\": \"{}\"}},\"RelyingParty\": \"http://auth.xboxlive.com\",\"TokenType\": \"JWT\"}}", ms_token_info.access_token());
 
-    let response = client.post("https://user.auth.xboxlive.com/user/authenticate").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send()?.text()?;
+    let response = client.post("https://user.auth.xboxlive.com/user/authenticate").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send()?.error_for_status()?.text()?;
 
-    let xbox_token_info: XboxLiveTokenInfo = serde_json::from_str(response.as_str()).unwrap();
+    let xbox_token_info: XboxLiveTokenInfo = serde_json::from_str(response.as_str())?;
 
     // get XSTS token
 
     let body = format!("{{\"Properties\": {{\"SandboxId\": \"RETAIL\",\"UserTokens\": [\"{}\"]}},\"RelyingParty\": \"rp://api.minecraftservices.com/\",\"TokenType\": \"JWT\"}}", xbox_token_info.token());
 
-    let response = client.post("https://xsts.auth.xboxlive.com/xsts/authorize").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send()?.text()?;
+    let response = client.post("https://xsts.auth.xboxlive.com/xsts/authorize").header("Content-Type", "application/json").header("Accept", "application/json").body(body).send()?.error_for_status()?.text()?;
 
     //println!("Xsts Response: {}", response.as_str());
 
@@ -86,22 +202,155 @@ pub fn login(username: String, password: String) -> Result<LoginState, LauncherE
 
     // get Minecraft Token
 
-    let body = format!("{{\"identityToken\" : \"XBL3.0 x={};{}\",\"ensureLegacyEnabled\" : true}}", xsts_token_info.iter().next().unwrap().as_str(), xsts_token_info.token());
+    let user_hash = xsts_token_info.first().ok_or(LauncherError::AccountError)?;
+    let body = format!("{{\"identityToken\" : \"XBL3.0 x={};{}\",\"ensureLegacyEnabled\" : true}}", user_hash.as_str(), xsts_token_info.token());
 
-    let response = client.post("https://api.minecraftservices.com/authentication/login_with_xbox").header("Content-Type", "application/json").body(body).send()?.text()?;
+    let response = client.post("https://api.minecraftservices.com/authentication/login_with_xbox").header("Content-Type", "application/json").body(body).send()?.error_for_status()?.text()?;
 
-    let minecraft_token_info: MinecraftTokenInfo = serde_json::from_str(response.as_str()).unwrap();
+    let minecraft_token_info: MinecraftTokenInfo = serde_json::from_str(response.as_str())?;
 
     //println!("Minecraft Token: {}", minecraft_token_info.access_token());
 
-    let response = client.get("https://api.minecraftservices.com/minecraft/profile").header("Authorization", format!("Bearer {}", minecraft_token_info.access_token())).send()?.text()?;
+    let owns_game = check_owns_game(client, minecraft_token_info.access_token())?;
+
+    // Not found just means there is no profile, which is handled below
+    let response = fail_on_transient_status(client.get("https://api.minecraftservices.com/minecraft/profile").header("Authorization", format!("Bearer {}", minecraft_token_info.access_token())).send()?)?.text()?;
 
     //println!("Account Info: {}", &response);
 
-    let profile_info: MinecraftAccountInfo = serde_json::from_str(response.as_str()).unwrap();
+    let profile_info: MinecraftAccountInfo = match serde_json::from_str(response.as_str()) {
+        Ok(profile_info) => profile_info,
+        // Accounts without a license don't have a profile
+        Err(_) if !owns_game => MinecraftAccountInfo::demo(minecraft_token_info.username().to_owned()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(LoggedInAccount::new(ms_token_info, xbox_token_info, xsts_token_info, minecraft_token_info, profile_info, owns_game))
+}
+
+
+#[derive(Deserialize, Debug, Clone)]
+struct Entitlements {
+    items: Vec<Entitlement>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Entitlement {
+    name: String,
+}
+
+///
+/// Asks the Minecraft services whether the account has a license for Java Edition. Accounts without one can only play the demo.
+///
+pub fn check_owns_game(client: &blocking::Client, minecraft_token: &str) -> Result<bool, LauncherError> {
+    let response = client.get("https://api.minecraftservices.com/entitlements/mcstore").header("Authorization", format!("Bearer {}", minecraft_token)).send()?.error_for_status()?.text()?;
+    let entitlements: Entitlements = serde_json::from_str(response.as_str())?;
+    Ok(entitlements.items.iter().any(|e| e.name == "product_minecraft" || e.name == "game_minecraft"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use crate::launcher_rewrite::authentication::login::{is_transient_error, login_client, MicrosoftOAuth};
+    use crate::launcher_rewrite::error::LauncherError;
+
+    ///
+    /// Answers each request with the next response, like the Microsoft token endpoints would
+    ///
+    fn mock_token_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                reader.by_ref().take(content_length).read_to_end(&mut Vec::new()).unwrap();
+                let response = format!("HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+                reader.into_inner().write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn device_code_flow_polls_until_signed_in() {
+        let server = mock_token_server(vec![
+            (200, r#"{"user_code": "ABCD1234", "device_code": "device", "verification_uri": "https://www.microsoft.com/link", "expires_in": 60, "interval": 0}"#),
+            (400, r#"{"error": "authorization_pending", "error_description": "The user hasn't signed in yet"}"#),
+            (200, r#"{"token_type": "bearer", "expires_in": 86400, "scope": "service::user.auth.xboxlive.com::MBI_SSL", "access_token": "access", "refresh_token": "refresh", "user_id": "user"}"#),
+        ]);
+        let oauth = MicrosoftOAuth::new(format!("{}/devicecode", server), format!("{}/token", server), String::from("client"), String::from("scope"));
+        let client = login_client();
+
+        let device_code = oauth.request_device_code(&client).unwrap();
+        assert_eq!(device_code.user_code(), "ABCD1234");
+        let token = oauth.poll_for_token(&client, &device_code).unwrap();
+        assert_eq!(token.access_token(), "access");
+        assert_eq!(token.refresh_token(), "refresh");
+    }
+
+    #[test]
+    fn device_code_flow_reports_declined_login() {
+        let server = mock_token_server(vec![
+            (200, r#"{"user_code": "ABCD1234", "device_code": "device", "verification_uri": "https://www.microsoft.com/link", "expires_in": 60, "interval": 0}"#),
+            (400, r#"{"error": "authorization_declined"}"#),
+        ]);
+        let oauth = MicrosoftOAuth::new(format!("{}/devicecode", server), format!("{}/token", server), String::from("client"), String::from("scope"));
+        let client = login_client();
+
+        let device_code = oauth.request_device_code(&client).unwrap();
+        assert!(matches!(oauth.poll_for_token(&client, &device_code), Err(LauncherError::LoginDeclined)));
+    }
+
+    #[test]
+    fn refresh_grant_returns_new_token() {
+        let server = mock_token_server(vec![
+            (200, r#"{"token_type": "bearer", "expires_in": 86400, "scope": "service::user.auth.xboxlive.com::MBI_SSL", "access_token": "new access", "refresh_token": "new refresh", "user_id": "user"}"#),
+        ]);
+        let oauth = MicrosoftOAuth::new(format!("{}/devicecode", server), format!("{}/token", server), String::from("client"), String::from("scope"));
+
+        let token = oauth.refresh(&login_client(), "refresh").unwrap();
+        assert_eq!(token.access_token(), "new access");
+        assert_eq!(token.refresh_token(), "new refresh");
+    }
+
+    #[test]
+    fn refresh_grant_reports_revoked_token() {
+        let server = mock_token_server(vec![
+            (400, r#"{"error": "invalid_grant", "error_description": "The refresh token has expired"}"#),
+        ]);
+        let oauth = MicrosoftOAuth::new(format!("{}/devicecode", server), format!("{}/token", server), String::from("client"), String::from("scope"));
+
+        let error = oauth.refresh(&login_client(), "refresh").unwrap_err();
+        // Has to be logged in again instead of falling back to the old tokens
+        assert!(!is_transient_error(&error));
+        assert!(matches!(error, LauncherError::OAuthError { error, .. } if error == "invalid_grant"));
+    }
 
-    let complete_token_info = LoggedInAccount::new(ms_token_info, xbox_token_info, xsts_token_info, minecraft_token_info, profile_info);
+    #[test]
+    fn refresh_grant_reports_server_errors_as_transient() {
+        let server = mock_token_server(vec![
+            (503, r#"<html>Service Unavailable</html>"#),
+            (429, r#"{"message": "Too Many Requests"}"#),
+        ]);
+        let oauth = MicrosoftOAuth::new(format!("{}/devicecode", server), format!("{}/token", server), String::from("client"), String::from("scope"));
 
-    LOGGED_IN_ACCOUNT_DATA.write().unwrap().add_account_and_set_active(complete_token_info);
-    Ok(LoggedIn)
+        assert!(oauth.refresh(&login_client(), "refresh").is_err_and(|e| is_transient_error(&e)));
+        assert!(oauth.refresh(&login_client(), "refresh").is_err_and(|e| is_transient_error(&e)));
+    }
 }
@@ -1,15 +1,62 @@
 use std::fs;
 use std::sync::{LazyLock, RwLock};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use rand::random;
+use serde::{Deserialize, Serialize};
 use crate::launcher_rewrite::authentication::account_data::{AccountData, get_account_data};
+use crate::launcher_rewrite::authentication::login::{is_transient_error, refresh_account};
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
 use crate::launcher_rewrite::installed_versions::INSTALLED_VERSIONS;
-use crate::launcher_rewrite::path_handler::{INSTALLED_VERSIONS_FILE_PATH, TOKENS_FILE_PATH};
-use crate::launcher_rewrite::util::config_file::save_to_file;
+use crate::launcher_rewrite::path_handler::{CLIENT_ID_FILE_PATH, INSTALLED_VERSIONS_FILE_PATH, TOKENS_FILE_PATH};
+use crate::launcher_rewrite::util::config_file::{load_from_file, save_to_file};
 
 pub mod account_data;
 pub mod login;
 
 pub static LOGGED_IN_ACCOUNT_DATA: LazyLock<RwLock<AccountData>> = LazyLock::new(|| RwLock::new(get_account_data()));
 
+// Identifies this launcher install to the game, generated on first use and kept across launches like the official launcher's client token
+pub static CLIENT_ID: LazyLock<String> = LazyLock::new(|| load_from_file::<ClientIdentity>(CLIENT_ID_FILE_PATH.as_path(), true).client_id);
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ClientIdentity {
+    client_id: String,
+}
+
+impl Default for ClientIdentity {
+    fn default() -> Self {
+        Self { client_id: BASE64_STANDARD.encode(random::<u128>().to_be_bytes()) }
+    }
+}
+
+///
+/// Refreshes the tokens of the active account if they expired or are about to, and saves them.
+/// Fails with `ReLoginRequired` if the account can't be refreshed, but keeps the old tokens when offline or the servers are having trouble so that way singleplayer still works.
+///
+pub fn ensure_active_account_fresh() -> LauncherResult<()> {
+    let account = {
+        let lock = LOGGED_IN_ACCOUNT_DATA.read().unwrap();
+        let account = lock.active_account().ok_or(LauncherError::NoActiveAccount)?;
+        if !account.needs_refresh() {
+            return Ok(());
+        }
+        account.clone()
+    };
+    match refresh_account(&account) {
+        Ok(refreshed) => {
+            LOGGED_IN_ACCOUNT_DATA.write().unwrap().replace_account(account.minecraft_account_info().id(), refreshed);
+            save_account_data();
+            Ok(())
+        }
+        Err(e) if is_transient_error(&e) => {
+            eprintln!("Couldn't refresh the session, launching with the old one. {e}");
+            Ok(())
+        }
+        Err(e) => Err(LauncherError::ReLoginRequired { account_name: account.minecraft_account_info().name().to_owned(), cause: Box::new(e) }),
+    }
+}
+
 pub fn save_account_data() {
     #[cfg(debug_assertions)]
     save_to_file(&*LOGGED_IN_ACCOUNT_DATA.read().unwrap(), TOKENS_FILE_PATH.as_path(), true);
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::SystemTime;
+
+const CRASH_REPORTS_FOLDER: &str = "crash-reports";
+
+///
+/// How a game instance ended, and the crash report it left behind if there is one
+///
+#[derive(Debug, Clone)]
+pub struct GameExit {
+    // None when the process was killed by a signal or couldn't be waited on
+    exit_code: Option<i32>,
+    crash_report: Option<PathBuf>,
+    game_dir: PathBuf,
+    // The user killed the game from the launcher, so a missing exit code isn't a crash
+    force_stopped: bool,
+}
+
+impl GameExit {
+    pub fn new(status: Option<ExitStatus>, game_dir: &Path, session_start: SystemTime, force_stopped: bool) -> Self {
+        Self {
+            exit_code: status.and_then(|s| s.code()),
+            crash_report: find_crash_report(game_dir, session_start),
+            game_dir: game_dir.to_owned(),
+            force_stopped,
+        }
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn crash_report(&self) -> Option<&Path> {
+        self.crash_report.as_deref()
+    }
+
+    pub fn game_dir(&self) -> &Path {
+        &self.game_dir
+    }
+
+    pub fn is_crash(&self) -> bool {
+        self.crash_report.is_some() || (!self.force_stopped && self.exit_code != Some(0))
+    }
+
+    pub fn force_stopped(&self) -> bool {
+        self.force_stopped
+    }
+
+    ///
+    /// The folder the user most likely wants to look at after a crash
+    ///
+    pub fn report_folder(&self) -> &Path {
+        self.crash_report.as_deref().and_then(Path::parent).unwrap_or(&self.game_dir)
+    }
+}
+
+///
+/// Finds the newest `crash-reports/crash-*.txt` or JVM `hs_err_pid*.log` written in `game_dir` since the session started
+///
+pub fn find_crash_report(game_dir: &Path, session_start: SystemTime) -> Option<PathBuf> {
+    let crash_reports = report_candidates(&game_dir.join(CRASH_REPORTS_FOLDER), |name| name.starts_with("crash-") && name.ends_with(".txt"));
+    let jvm_reports = report_candidates(game_dir, |name| name.starts_with("hs_err_pid") && name.ends_with(".log"));
+
+    crash_reports.chain(jvm_reports)
+        .filter_map(|path| fs::metadata(&path).and_then(|m| m.modified()).ok().map(|modified| (path, modified)))
+        .filter(|(_, modified)| *modified >= session_start)
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(path, _)| path)
+}
+
+fn report_candidates(dir: &Path, matches_name: fn(&str) -> bool) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(dir).into_iter().flatten().flatten()
+        .filter(move |entry| entry.file_name().to_str().is_some_and(matches_name))
+        .map(|entry| entry.path())
+}
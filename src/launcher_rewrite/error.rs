@@ -1,7 +1,9 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter, Write};
 use std::io;
+use std::path::PathBuf;
 use crate::launcher_rewrite::error::LauncherError::ProfileError;
+use crate::launcher_rewrite::profiles::ModLoader;
 
 pub type LauncherResult<T> = Result<T, LauncherError>;
 
@@ -13,11 +15,67 @@ pub enum LauncherError {
     ExtractError(zip::result::ZipError),
     AccountError,
     ProfileError,
+    // The mod loader does not have any builds for the game version
+    NoLoaderForGameVersion { loader: ModLoader, game_version: String },
+    // The requested loader version (or `latest-stable`) could not be found for the game version
+    LoaderVersionNotFound { loader: ModLoader, game_version: String, loader_version: String },
+    // Mojang does not provide the java runtime component for this platform
+    JavaRuntimeUnavailable { component: String },
+    // A downloaded file does not match the hash it is supposed to have
+    FileVerificationFailed(PathBuf),
+    // No java installation could be found on the system
+    JavaNotFound,
+    // The chosen java executable could not be run
+    InvalidJavaExecutable(PathBuf),
+    // The chosen java is older than what the game version requires
+    IncompatibleJava { executable: PathBuf, required: u8, found: u8 },
+    // The additional jvm arguments of the profile could not be parsed
+    InvalidJvmArguments { reason: &'static str },
+    // There is no account to launch the game with
+    NoActiveAccount,
+    // The wrapper command of the profile could not be parsed
+    InvalidWrapperCommand { reason: &'static str },
+    // The pre-launch hook exited unsuccessfully, so the game wasn't started
+    PreLaunchHookFailed { exit_code: Option<i32> },
+    // The instance folder can't be renamed or deleted while the game is running from it
+    InstanceDirInUse(PathBuf),
+    // The device code expired before the user signed in
+    LoginExpired,
+    // The user declined to sign in
+    LoginDeclined,
+    // The Microsoft token endpoint returned an error
+    OAuthError { error: String, description: Option<String> },
+    // The session of the account expired and couldn't be refreshed
+    ReLoginRequired { account_name: String, cause: Box<LauncherError> },
+    // Versions before 1.20 can only join a server directly, not open a world or realm
+    QuickPlayUnsupported { game_version: String },
 }
 
 impl Display for LauncherError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{:?}", self))
+        use LauncherError::*;
+        match self {
+            NoLoaderForGameVersion { loader, game_version } => write!(f, "{} does not have any loader versions for game version `{}`", loader, game_version),
+            LoaderVersionNotFound { loader, game_version, loader_version } => write!(f, "Unable to find {} loader version `{}` for game version `{}`", loader, loader_version, game_version),
+            JavaRuntimeUnavailable { component } => write!(f, "The java runtime `{}` is not available for this platform, and no installed java is new enough. Install a newer java or choose one in the installation settings.", component),
+            FileVerificationFailed(path) => write!(f, "The downloaded file {} is corrupted", path.display()),
+            JavaNotFound => write!(f, "Unable to find a java installation"),
+            InvalidJavaExecutable(path) => write!(f, "{} is not a working java executable", path.display()),
+            IncompatibleJava { executable, required, found } => write!(f, "This version of Minecraft requires Java {} or newer, but {} is Java {}. Use the recommended runtime instead.", required, executable.display(), found),
+            InvalidJvmArguments { reason } => write!(f, "Invalid additional JVM arguments: {}", reason),
+            NoActiveAccount => write!(f, "No account is logged in. Add an account before launching the game."),
+            InvalidWrapperCommand { reason } => write!(f, "Invalid wrapper command: {}", reason),
+            PreLaunchHookFailed { exit_code: Some(code) } => write!(f, "The pre-launch hook failed with exit code {}", code),
+            PreLaunchHookFailed { exit_code: None } => write!(f, "The pre-launch hook was terminated"),
+            InstanceDirInUse(path) => write!(f, "The instance folder {} is in use by a running game", path.display()),
+            LoginExpired => write!(f, "The login code expired, please try again"),
+            LoginDeclined => write!(f, "The login was declined"),
+            OAuthError { error, description: Some(description) } => write!(f, "Login failed: {} ({})", description, error),
+            OAuthError { error, description: None } => write!(f, "Login failed: {}", error),
+            ReLoginRequired { account_name, cause } => write!(f, "The session of {} expired and couldn't be refreshed, please log in again. {}", account_name, cause),
+            QuickPlayUnsupported { game_version } => write!(f, "Minecraft {} can't start in a singleplayer world or realm, only join a server. Change the quick play setting of the installation.", game_version),
+            other => f.write_fmt(format_args!("{:?}", other)),
+        }
     }
 }
 
@@ -29,7 +87,8 @@ impl Error for LauncherError {
             FsError(e) => Some(e),
             DownloadError(e) => Some(e),
             ExtractError(e) => Some(e),
-            AccountError | ProfileError => None,
+            AccountError | ProfileError | NoLoaderForGameVersion { .. } | LoaderVersionNotFound { .. } | JavaRuntimeUnavailable { .. } | FileVerificationFailed(_) | JavaNotFound | InvalidJavaExecutable(_) | IncompatibleJava { .. } | InvalidJvmArguments { .. } | NoActiveAccount | InvalidWrapperCommand { .. } | PreLaunchHookFailed { .. } | InstanceDirInUse(_) | LoginExpired | LoginDeclined | OAuthError { .. } | QuickPlayUnsupported { .. } => None,
+            ReLoginRequired { cause, .. } => Some(cause.as_ref()),
         }
     }
     
@@ -40,7 +40,7 @@ impl Downloadable for ForgeJarDownloadable<'_> {
     }
 }
 
-pub fn download(loader_info: &ModLoaderVersionInfo, game_version: &str) -> Result<(), LauncherError> {
+pub fn download(loader_info: &ModLoaderVersionInfo, game_version: &str, java: &Path) -> Result<(), LauncherError> {
 
     let loader_version = loader_info.version_name();
     let game_version = GAME_VERSION_MANIFEST.sanitize_version_name(game_version, ModLoader::Forge);
@@ -90,7 +90,7 @@ pub fn download(loader_info: &ModLoaderVersionInfo, game_version: &str) -> Resul
     // Just run the forge installer so that way we don't have to patch the jar ourselves (For now)
     // Fake it till you make it. -Kyle Schmerge 2024
     fs::write(dummy_profiles_json_path.as_path(), [])?;
-    let _ = Command::new("java").current_dir(temp_path.as_path()).args(["-jar", temp_path.to_string_lossy().as_ref(), "--installClient", install_dir.to_string_lossy().as_ref()]).output();
+    let _ = Command::new(java).current_dir(temp_path.as_path()).args(["-jar", temp_path.to_string_lossy().as_ref(), "--installClient", install_dir.to_string_lossy().as_ref()]).output();
     //println!("path: {:?}", generated_client_path.as_path());
     let mut generated = File::open(generated_client_path.as_path())?;
     let mut bin_file = File::create(forge_client_path.as_path())?;
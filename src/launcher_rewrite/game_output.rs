@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::thread::JoinHandle;
+use crate::launcher_rewrite::crash::GameExit;
+use crate::launcher_rewrite::log4j::{Log4jStreamParser, LogEvent, LogRecord};
+
+// Lines beyond this are dropped from the front of the buffer, so a long session doesn't eat all the memory
+const MAX_BUFFERED_LINES: usize = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputSource {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 6] = [LogLevel::Trace, LogLevel::Debug, LogLevel::Info, LogLevel::Warn, LogLevel::Error, LogLevel::Fatal];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+            LogLevel::Fatal => "FATAL",
+        }
+    }
+
+    pub fn from_str(level: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.as_str().eq_ignore_ascii_case(level))
+    }
+
+    ///
+    /// Finds the level in a line formatted by the game's default pattern, e.g. `[12:00:00] [Render thread/INFO]: ...`
+    ///
+    fn detect(line: &str) -> Option<Self> {
+        let (_, rest) = line.split_once("/")?;
+        let (level, _) = rest.split_once("]")?;
+        Self::from_str(level)
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    source: OutputSource,
+    level: LogLevel,
+    text: String,
+    // Present when the game wrote this line through its XML logger
+    event: Option<LogEvent>,
+}
+
+impl OutputLine {
+    fn new(source: OutputSource, record: LogRecord, previous_level: Option<LogLevel>) -> Self {
+        match record {
+            LogRecord::Event(event) => Self { source, level: event.level(), text: event.to_plain_text(), event: Some(event) },
+            LogRecord::Text(text) => {
+                // Lines without a level (stack traces, wrapped messages) belong to the line before them
+                let level = LogLevel::detect(&text).or(previous_level).unwrap_or(match source {
+                    OutputSource::Stdout => LogLevel::Info,
+                    OutputSource::Stderr => LogLevel::Error,
+                });
+                Self { source, level, text, event: None }
+            }
+        }
+    }
+
+    pub fn source(&self) -> OutputSource {
+        self.source
+    }
+
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn event(&self) -> Option<&LogEvent> {
+        self.event.as_ref()
+    }
+}
+
+///
+/// Output of a single game instance, filled by reader threads while the game is running
+///
+#[derive(Debug)]
+pub struct GameOutput {
+    version_id: String,
+    lines: Mutex<VecDeque<OutputLine>>,
+    // Total number of lines ever received, including the ones that were dropped from the buffer
+    received: AtomicUsize,
+    // Filled in by the game process thread once the game has exited
+    exit: Mutex<Option<GameExit>>,
+    exited: Condvar,
+}
+
+impl GameOutput {
+    pub fn new(version_id: String) -> Self {
+        Self {
+            version_id,
+            lines: Mutex::new(VecDeque::new()),
+            received: AtomicUsize::new(0),
+            exit: Mutex::new(None),
+            exited: Condvar::new(),
+        }
+    }
+
+    pub fn version_id(&self) -> &str {
+        &self.version_id
+    }
+
+    pub fn lines(&self) -> MutexGuard<'_, VecDeque<OutputLine>> {
+        self.lines.lock().unwrap()
+    }
+
+    pub fn received(&self) -> usize {
+        self.received.load(Ordering::SeqCst)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.exit.lock().unwrap().is_none()
+    }
+
+    pub fn exit(&self) -> Option<GameExit> {
+        self.exit.lock().unwrap().clone()
+    }
+
+    pub fn set_exited(&self, exit: GameExit) {
+        *self.exit.lock().unwrap() = Some(exit);
+        self.exited.notify_all();
+    }
+
+    ///
+    /// Blocks until the game has exited
+    ///
+    pub fn wait_for_exit(&self) -> GameExit {
+        let exit = self.exited.wait_while(self.exit.lock().unwrap(), |exit| exit.is_none()).unwrap();
+        exit.clone().unwrap()
+    }
+
+    ///
+    /// The newest `n` lines, oldest first
+    ///
+    pub fn last_lines(&self, n: usize) -> Vec<String> {
+        let lines = self.lines();
+        lines.iter().skip(lines.len().saturating_sub(n)).map(|l| l.text.clone()).collect()
+    }
+
+    pub fn push_record(&self, source: OutputSource, record: LogRecord) {
+        // Only one thread pushes lines per source, so the level can't change before the line is added below
+        let previous_level = self.lines().iter().rev().find(|l| l.source == source).map(|l| l.level);
+        let line = OutputLine::new(source, record, previous_level);
+        // Echoed without holding the lock, so a slow terminal doesn't hold up the gui
+        match source {
+            OutputSource::Stdout => println!("{}", line.text),
+            OutputSource::Stderr => eprintln!("{}", line.text),
+        }
+        let mut lines = self.lines();
+        lines.push_back(line);
+        if lines.len() > MAX_BUFFERED_LINES {
+            lines.pop_front();
+        }
+        self.received.fetch_add(1, Ordering::SeqCst);
+    }
+
+    ///
+    /// Spawns a thread that copies every line of `stream` into this buffer, and echoes it to the launcher's own output.
+    /// The game logs to stdout through log4j's `XMLLayout`, so those events are parsed back into structured records.
+    /// Returns the thread, which ends once the stream is closed, or None if it couldn't be spawned
+    ///
+    pub fn capture<R: Read + Send + 'static>(self: &Arc<Self>, source: OutputSource, stream: R) -> Option<JoinHandle<()>> {
+        let output = self.clone();
+        let name = match source {
+            OutputSource::Stdout => "Game Stdout Thread",
+            OutputSource::Stderr => "Game Stderr Thread",
+        };
+        thread::Builder::new().name(name.to_owned()).spawn(move || {
+            let mut parser = Log4jStreamParser::new();
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                let records = match source {
+                    OutputSource::Stdout => parser.push_line(&line),
+                    OutputSource::Stderr => vec![LogRecord::Text(line)],
+                };
+                for record in records {
+                    output.push_record(source, record);
+                }
+            }
+            if let Some(record) = parser.finish() {
+                output.push_record(source, record);
+            }
+        }).ok()
+    }
+}
@@ -0,0 +1,108 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
+use crate::launcher_rewrite::profiles::{LauncherProfile, ModLoader};
+use crate::launcher_rewrite::util::shell_args::split_shell_args;
+
+// Environment variables describing the instance, set for the hooks, the wrapper and the game itself
+pub const ENV_PROFILE_NAME: &str = "LAUNCHER_PROFILE_NAME";
+pub const ENV_GAME_DIR: &str = "LAUNCHER_GAME_DIR";
+pub const ENV_GAME_VERSION: &str = "LAUNCHER_GAME_VERSION";
+pub const ENV_MOD_LOADER: &str = "LAUNCHER_MOD_LOADER";
+pub const ENV_LOADER_VERSION: &str = "LAUNCHER_LOADER_VERSION";
+// Only set for the post-exit hook, and left empty when the game has no exit code
+pub const ENV_EXIT_CODE: &str = "LAUNCHER_EXIT_CODE";
+
+///
+/// Commands a profile runs around the game. Hooks are run through the system shell, the wrapper is split like jvm arguments and put in front of java.
+///
+#[derive(Debug, Clone, Default)]
+pub struct LaunchHooks {
+    profile_name: String,
+    wrapper_command: Option<String>,
+    pre_launch: Option<String>,
+    post_exit: Option<String>,
+}
+
+impl LaunchHooks {
+    pub fn new(profile_name: String, wrapper_command: Option<String>, pre_launch: Option<String>, post_exit: Option<String>) -> Self {
+        Self { profile_name, wrapper_command, pre_launch, post_exit }
+    }
+
+    pub fn from_profile(profile: &LauncherProfile) -> Self {
+        Self::new(profile.name().to_owned(), profile.wrapper_command().map(str::to_owned), profile.pre_launch_hook().map(str::to_owned), profile.post_exit_hook().map(str::to_owned))
+    }
+
+    pub fn pre_launch(&self) -> Option<&str> {
+        self.pre_launch.as_deref()
+    }
+
+    pub fn post_exit(&self) -> Option<&str> {
+        self.post_exit.as_deref()
+    }
+
+    pub fn wrapper_args(&self) -> LauncherResult<Vec<String>> {
+        self.wrapper_command.as_deref().map(split_shell_args).transpose().map_err(|reason| LauncherError::InvalidWrapperCommand { reason }).map(Option::unwrap_or_default)
+    }
+
+    pub fn environment(&self, game_dir: &Path, game_version: &str, mod_loader: ModLoader, loader_version: Option<&str>) -> Vec<(&'static str, OsString)> {
+        vec![
+            (ENV_PROFILE_NAME, self.profile_name.as_str().into()),
+            (ENV_GAME_DIR, game_dir.as_os_str().to_owned()),
+            (ENV_GAME_VERSION, game_version.into()),
+            (ENV_MOD_LOADER, mod_loader.to_string().into()),
+            (ENV_LOADER_VERSION, loader_version.unwrap_or_default().into()),
+        ]
+    }
+
+    ///
+    /// Runs the pre-launch hook and waits for it. The launch has to be aborted if this fails.
+    ///
+    pub fn run_pre_launch(&self, game_dir: &Path, environment: &[(&'static str, OsString)]) -> LauncherResult<()> {
+        let Some(hook) = &self.pre_launch else { return Ok(()) };
+        let status = shell_command(hook).current_dir(game_dir).envs(environment.iter().cloned()).status()?;
+        if !status.success() {
+            return Err(LauncherError::PreLaunchHookFailed { exit_code: status.code() });
+        }
+        Ok(())
+    }
+
+    ///
+    /// The post-exit hook, ready to run once the game has exited
+    ///
+    pub fn post_exit_command(&self, game_dir: &Path, environment: &[(&'static str, OsString)]) -> Option<Command> {
+        let hook = self.post_exit.as_deref()?;
+        let mut cmd = shell_command(hook);
+        cmd.current_dir(game_dir).envs(environment.iter().cloned());
+        Some(cmd)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(hook: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(hook);
+    cmd
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_command(hook: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(hook);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::launcher_rewrite::error::LauncherError;
+    use crate::launcher_rewrite::hooks::LaunchHooks;
+
+    #[test]
+    fn failing_pre_launch_hook_aborts() {
+        let hooks = LaunchHooks::new(String::from("Test"), None, Some(String::from("exit 3")), None);
+        let result = hooks.run_pre_launch(Path::new("."), &[]);
+        assert!(matches!(result, Err(LauncherError::PreLaunchHookFailed { exit_code: Some(3) })));
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::thread;
+use std::thread::{sleep, JoinHandle};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use crate::launcher_rewrite::authentication::account_data::LoggedInAccount;
+use crate::launcher_rewrite::crash::GameExit;
+use crate::launcher_rewrite::error::LauncherResult;
+use crate::launcher_rewrite::game_output::GameOutput;
+use crate::launcher_rewrite::hooks::ENV_EXIT_CODE;
+use crate::launcher_rewrite::profiles::play_history::{save_play_history, LaunchRecord, PLAY_HISTORY};
+
+// Every game instance started by this launcher process. The launcher doesn't exit until this is empty, so closing the launcher doesn't take the games with it.
+pub static RUNNING_INSTANCES: LazyLock<RunningInstances> = LazyLock::new(RunningInstances::new);
+
+// How often the process thread checks whether the game has exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub struct RunningInstance {
+    // None when launched from the command line instead of a profile
+    profile_id: Option<u128>,
+    account_name: String,
+    pid: u32,
+    start_time: DateTime<Utc>,
+    game_version: String,
+    loader_version: Option<String>,
+    // Locked only briefly, so the game can be killed while the process thread is waiting for it
+    child: Mutex<Child>,
+    output: Arc<GameOutput>,
+    force_stopped: AtomicBool,
+}
+
+impl RunningInstance {
+    pub fn account_name(&self) -> &str {
+        &self.account_name
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn start_time(&self) -> DateTime<Utc> {
+        self.start_time
+    }
+
+    pub fn output(&self) -> &Arc<GameOutput> {
+        &self.output
+    }
+
+    ///
+    /// Kills the game process without letting it save
+    ///
+    pub fn force_stop(&self) -> LauncherResult<()> {
+        self.force_stopped.store(true, Ordering::SeqCst);
+        self.child.lock().unwrap().kill()?;
+        Ok(())
+    }
+
+    ///
+    /// Blocks until the game has exited
+    ///
+    pub fn wait_for_exit(&self) -> GameExit {
+        self.output.wait_for_exit()
+    }
+}
+
+#[derive(Debug)]
+pub struct RunningInstances {
+    instances: Mutex<Vec<Arc<RunningInstance>>>,
+    changed: Condvar,
+}
+
+impl RunningInstances {
+    fn new() -> Self {
+        Self {
+            instances: Mutex::new(Vec::new()),
+            changed: Condvar::new(),
+        }
+    }
+
+    ///
+    /// Starts tracking a game process that was just spawned. The instance is removed again once the game exits.
+    ///
+    pub fn register(&'static self, profile_id: Option<u128>, game_version: String, loader_version: Option<String>, account: &LoggedInAccount, child: Child, output: Arc<GameOutput>, output_threads: Vec<JoinHandle<()>>, post_exit_hook: Option<Command>, game_dir: PathBuf, session_start: SystemTime) -> Arc<RunningInstance> {
+        let instance = Arc::new(RunningInstance {
+            profile_id,
+            account_name: account.minecraft_account_info().name().to_owned(),
+            pid: child.id(),
+            start_time: DateTime::<Utc>::from(session_start),
+            game_version,
+            loader_version,
+            child: Mutex::new(child),
+            output,
+            force_stopped: AtomicBool::new(false),
+        });
+        self.instances.lock().unwrap().push(instance.clone());
+
+        let process_instance = instance.clone();
+        let _ = thread::Builder::new().name("Game Process Thread".to_owned()).spawn(move || {
+            // IMPORTANT: Panicking here would leave the instance registered forever, and the launcher would never exit!
+            let status = loop {
+                match process_instance.child.lock().unwrap().try_wait() {
+                    Ok(Some(status)) => break Some(status),
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("Failed to wait for game process. {e}");
+                        break None;
+                    }
+                }
+                sleep(EXIT_POLL_INTERVAL);
+            };
+            // The last lines, usually the stack trace of a crash, can still be on their way through the pipes
+            for output_thread in output_threads {
+                let _ = output_thread.join();
+            }
+            let exit = GameExit::new(status, &game_dir, session_start, process_instance.force_stopped.load(Ordering::SeqCst));
+            if exit.is_crash() {
+                eprintln!("Game exited abnormally with exit code {:?}. Crash report: {:?}", exit.exit_code(), exit.crash_report());
+            }
+            if let Some(profile_id) = process_instance.profile_id {
+                let duration = SystemTime::now().duration_since(session_start).unwrap_or_default();
+                let record = LaunchRecord::new(process_instance.start_time, process_instance.game_version.clone(), process_instance.loader_version.clone(), exit.exit_code(), duration.as_secs());
+                PLAY_HISTORY.write().unwrap().record_launch(profile_id, record);
+                // Saved right away, since the game can outlive the gui
+                save_play_history();
+            }
+            if let Some(mut post_exit_hook) = post_exit_hook {
+                let exit_code = exit.exit_code().map(|c| c.to_string()).unwrap_or_default();
+                match post_exit_hook.env(ENV_EXIT_CODE, exit_code).status() {
+                    Ok(status) if !status.success() => eprintln!("Post-exit hook failed with {}", status),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Failed to run post-exit hook. {e}"),
+                }
+            }
+            process_instance.output.set_exited(exit);
+            self.instances.lock().unwrap().retain(|i| !Arc::ptr_eq(i, &process_instance));
+            self.changed.notify_all();
+        });
+        instance
+    }
+
+    pub fn find_by_profile(&self, profile_id: u128) -> Vec<Arc<RunningInstance>> {
+        self.instances.lock().unwrap().iter().filter(|i| i.profile_id == Some(profile_id)).cloned().collect()
+    }
+
+    ///
+    /// Blocks until every game instance has exited
+    ///
+    pub fn wait_for_all(&self) {
+        drop(self.changed.wait_while(self.instances.lock().unwrap(), |instances| !instances.is_empty()).unwrap());
+    }
+}
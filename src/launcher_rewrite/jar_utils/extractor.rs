@@ -12,9 +12,32 @@ pub const NATIVE_JARS_SUFFIX: &str = "natives-windows-arm64.jar";
 pub const NATIVE_JARS_SUFFIX: &str = "natives-macos.jar";
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 pub const NATIVE_JARS_SUFFIX: &str = "natives-macos-arm64.jar";
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+pub const NATIVE_JARS_SUFFIX: &str = "natives-linux-arm64.jar";
+#[cfg(all(target_os = "linux", target_arch = "arm"))]
+pub const NATIVE_JARS_SUFFIX: &str = "natives-linux-arm32.jar";
+#[cfg(all(target_os = "linux", not(any(target_arch = "aarch64", target_arch = "arm"))))]
 pub const NATIVE_JARS_SUFFIX: &str = "natives-linux.jar";
 
+// Folders LWJGL uses inside natives jars for other architectures, natives in there are never extracted
+#[cfg(target_arch = "x86_64")]
+const FOREIGN_ARCH_FOLDERS: &[&str] = &["x86", "arm64", "arm32"];
+#[cfg(target_arch = "x86")]
+const FOREIGN_ARCH_FOLDERS: &[&str] = &["x64", "arm64", "arm32"];
+#[cfg(target_arch = "aarch64")]
+const FOREIGN_ARCH_FOLDERS: &[&str] = &["x64", "x86", "arm32"];
+#[cfg(target_arch = "arm")]
+const FOREIGN_ARCH_FOLDERS: &[&str] = &["x64", "x86", "arm64"];
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64", target_arch = "arm")))]
+const FOREIGN_ARCH_FOLDERS: &[&str] = &[];
+
+#[cfg(target_os = "windows")]
+pub const NATIVE_LIBRARY_EXTENSIONS: &[&str] = &[".dll"];
+#[cfg(target_os = "macos")]
+pub const NATIVE_LIBRARY_EXTENSIONS: &[&str] = &[".dylib", ".jnilib"];
+#[cfg(target_os = "linux")]
+pub const NATIVE_LIBRARY_EXTENSIONS: &[&str] = &[".so"];
+
 pub fn extract_if_needed(extracted_path: &Path, internal_path: &Path, jar_path: &Path) -> Result<(), LauncherError> {
     fs::create_dir_all(extracted_path.parent().unwrap())?;
 
@@ -34,20 +57,25 @@ pub fn extract_if_needed(extracted_path: &Path, internal_path: &Path, jar_path:
     Ok(())
 }
 
-pub fn extract_dlls_from_jar(bin_path: &Path, jar_path: &Path) -> Result<(), LauncherError> {
+pub fn extract_natives_from_jar(bin_path: &Path, jar_path: &Path, exclude: &[String]) -> Result<(), LauncherError> {
     // println!("Jar Path: {}", jar_path.display());
     let jar_file = fs::File::open(jar_path)?;
     let mut archive = zip::ZipArchive::new(jar_file)?;
     let mut extracted_file_path: PathBuf = bin_path.into();
 
-    let dll_names: Vec<String> = archive.file_names().filter(|n| n.ends_with(".dll")).map(String::from).collect();
-    dll_names.iter().map(|internal_name| {
+    let native_names: Vec<String> = archive.file_names()
+        .filter(|n| NATIVE_LIBRARY_EXTENSIONS.iter().any(|ext| n.ends_with(ext)))
+        .filter(|n| !exclude.iter().any(|prefix| n.starts_with(prefix.as_str())))
+        .filter(|n| !n.split('/').any(|folder| FOREIGN_ARCH_FOLDERS.contains(&folder)))
+        .map(String::from)
+        .collect();
+    native_names.iter().map(|internal_name| {
         let external_name = match internal_name.rsplit_once('/') {
             None => internal_name,
             Some((_, rhs)) => rhs,
         };
         extracted_file_path.push(external_name);
-        //println!("Extracting dll file: {} to {}", internal_name, extracted_file_path.as_os_str().to_str().unwrap());
+        //println!("Extracting native file: {} to {}", internal_name, extracted_file_path.as_os_str().to_str().unwrap());
         let mut internal_file = archive.by_name(internal_name)?;
         let mut external_file = fs::File::create(&extracted_file_path)?;
         io::copy(&mut internal_file, &mut external_file)?;
@@ -55,4 +83,4 @@ pub fn extract_dlls_from_jar(bin_path: &Path, jar_path: &Path) -> Result<(), Lau
         Ok(())
     }).collect::<Result<Vec<()>, LauncherError>>()?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+use crate::launcher_rewrite::path_handler::{from_launcher_dir, RUNTIMES_FOLDER};
+use crate::launcher_rewrite::util::paths::home_dir;
+
+#[cfg(target_os = "windows")]
+pub const JAVA_EXECUTABLE_NAME: &str = "java.exe";
+#[cfg(not(target_os = "windows"))]
+pub const JAVA_EXECUTABLE_NAME: &str = "java";
+
+// Folders that contain one jdk per sub folder
+#[cfg(target_os = "windows")]
+const JDK_PARENT_FOLDERS: &[&str] = &[
+    "C:\\Program Files\\Java",
+    "C:\\Program Files (x86)\\Java",
+    "C:\\Program Files\\Eclipse Adoptium",
+    "C:\\Program Files\\Microsoft",
+    "C:\\Program Files\\Zulu",
+    "C:\\Program Files\\BellSoft",
+    "C:\\Program Files\\Amazon Corretto",
+];
+#[cfg(target_os = "macos")]
+const JDK_PARENT_FOLDERS: &[&str] = &[
+    "/Library/Java/JavaVirtualMachines",
+    "/System/Library/Java/JavaVirtualMachines",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const JDK_PARENT_FOLDERS: &[&str] = &[
+    "/usr/lib/jvm",
+    "/usr/lib64/jvm",
+    "/usr/java",
+    "/opt/java",
+    "/opt/jdk",
+    "/opt/jdks",
+];
+
+// Same as above, but relative to the home directory
+const HOME_JDK_PARENT_FOLDERS: &[&[&str]] = &[
+    &[".sdkman", "candidates", "java"],
+    &[".jdks"],
+    &[".gradle", "jdks"],
+    &[".asdf", "installs", "java"],
+];
+
+pub static JAVA_INSTALLATIONS: LazyLock<Vec<JavaInstallation>> = LazyLock::new(discover_java_installations);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JavaInstallation {
+    executable: PathBuf,
+    version: String,
+    major_version: u8,
+    vendor: String,
+    arch: String,
+}
+
+impl JavaInstallation {
+    pub fn executable(&self) -> &Path {
+        &self.executable
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn major_version(&self) -> u8 {
+        self.major_version
+    }
+
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    pub fn arch(&self) -> &str {
+        &self.arch
+    }
+}
+
+impl Display for JavaInstallation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Java {} ({}, {}) - {}", self.version, self.vendor, self.arch, self.executable.display())
+    }
+}
+
+///
+/// Looks for java installations in `JAVA_HOME`, `PATH`, the usual system install locations, SDKMAN and the launcher's own runtimes.
+/// Every executable found is run once to find out its version, so this takes a while and should never run on the gui thread.
+///
+/// [`JAVA_INSTALLATIONS`] caches the result for the lifetime of the launcher, which is enough for picking a java to launch with.
+/// Call this directly to scan again, e.g. for the list in the installation editor, which should include runtimes downloaded since startup.
+///
+pub fn discover_java_installations() -> Vec<JavaInstallation> {
+    let mut homes: Vec<PathBuf> = Vec::new();
+    let mut executables: Vec<PathBuf> = Vec::new();
+
+    if let Some(java_home) = env::var_os("JAVA_HOME") {
+        homes.push(java_home.into());
+    }
+    if let Some(path) = env::var_os("PATH") {
+        executables.extend(env::split_paths(&path).map(|dir| dir.join(JAVA_EXECUTABLE_NAME)));
+    }
+
+    let mut parent_folders: Vec<PathBuf> = JDK_PARENT_FOLDERS.iter().map(PathBuf::from).collect();
+    if let Some(home) = home_dir() {
+        parent_folders.extend(HOME_JDK_PARENT_FOLDERS.iter().map(|p| p.iter().fold(home.clone(), |path, element| path.join(element))));
+    }
+    parent_folders.push(from_launcher_dir([RUNTIMES_FOLDER]));
+    for folder in parent_folders {
+        if let Ok(entries) = fs::read_dir(folder) {
+            homes.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+        }
+    }
+
+    for home in homes {
+        executables.push(home.join("bin").join(JAVA_EXECUTABLE_NAME));
+        // macOS bundles, including the runtimes downloaded by the launcher
+        executables.push(home.join("Contents").join("Home").join("bin").join(JAVA_EXECUTABLE_NAME));
+        executables.push(home.join("jre.bundle").join("Contents").join("Home").join("bin").join(JAVA_EXECUTABLE_NAME));
+    }
+
+    // The same installation is often reachable through symlinks, e.g. `/usr/bin/java`
+    let mut seen = HashSet::new();
+    executables.into_iter()
+        .filter(|e| e.is_file())
+        .filter(|e| seen.insert(fs::canonicalize(e).unwrap_or_else(|_| e.clone())))
+        .filter_map(|e| probe_java_installation(&e))
+        .collect()
+}
+
+///
+/// Runs `java -XshowSettings:properties -version` to find out the version, vendor and architecture of the java executable.
+/// Returns `None` if it isn't a working java executable.
+///
+pub fn probe_java_installation(executable: &Path) -> Option<JavaInstallation> {
+    let output = Command::new(executable).args(["-XshowSettings:properties", "-version"]).output().ok()?;
+    // The properties are printed to stderr
+    let properties = String::from_utf8_lossy(&output.stderr);
+    let property = |name: &str| properties.lines().find_map(|line| line.trim().strip_prefix(name)?.trim_start().strip_prefix('=').map(|v| v.trim().to_owned()));
+
+    let version = property("java.version")?;
+    let major_version = parse_major_version(&version)?;
+    Some(JavaInstallation {
+        executable: executable.to_owned(),
+        version,
+        major_version,
+        vendor: property("java.vendor").unwrap_or_default(),
+        arch: property("os.arch").unwrap_or_default(),
+    })
+}
+
+///
+/// Both `1.8.0_382` and `17.0.8` style versions are supported
+///
+pub fn parse_major_version(version: &str) -> Option<u8> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    match parts.next()? {
+        "1" => parts.next()?.parse().ok(),
+        major => major.parse().ok(),
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use serde::{Deserialize, Serialize};
+
+// Platform name -> Component name -> Available runtimes
+pub (in crate::launcher_rewrite::java) type RuntimeIndex<'file> = HashMap<&'file str, HashMap<&'file str, Vec<RuntimeVersion<'file>>>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub (in crate::launcher_rewrite::java) struct RuntimeVersion<'file> {
+    #[serde(borrow)]
+    pub manifest: DownloadInfo<'file>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub (in crate::launcher_rewrite::java) struct DownloadInfo<'file> {
+    #[serde(borrow)]
+    pub sha1: &'file str,
+    pub size: Option<NonZeroU64>,
+    #[serde(borrow)]
+    pub url: &'file str,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub (in crate::launcher_rewrite::java) struct RuntimeManifest<'file> {
+    #[serde(borrow)]
+    pub files: HashMap<&'file str, RuntimeFileInfo<'file>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub (in crate::launcher_rewrite::java) enum RuntimeFileInfo<'file> {
+    File {
+        #[serde(borrow)]
+        downloads: RuntimeFileDownloads<'file>,
+        #[serde(default)]
+        executable: bool,
+    },
+    Directory,
+    Link {
+        #[serde(borrow)]
+        target: &'file str,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub (in crate::launcher_rewrite::java) struct RuntimeFileDownloads<'file> {
+    #[serde(borrow)]
+    pub raw: DownloadInfo<'file>,
+}
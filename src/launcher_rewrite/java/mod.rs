@@ -0,0 +1,200 @@
+use std::fs;
+use std::fs::File;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use reqwest::Url;
+use serde::de::Error;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
+use crate::launcher_rewrite::installer::{DEFAULT_DOWNLOADER_CLIENT, Downloadable};
+use crate::launcher_rewrite::java::discovery::{probe_java_installation, JAVA_INSTALLATIONS};
+use crate::launcher_rewrite::launch_properties::JavaVersionInfo;
+use crate::launcher_rewrite::path_handler::{get_runtime_dir, get_runtime_manifest_path};
+use crate::launcher_rewrite::urls::JAVA_RUNTIME_MANIFEST_URL;
+use crate::launcher_rewrite::util::hash::{sha1_from_base64_str, sha1_matches, FileHash};
+use crate::launcher_rewrite::util::set_executable;
+
+pub mod discovery;
+mod internal;
+
+// Platform names used by Mojang's java runtime manifest
+#[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("windows-x64");
+#[cfg(all(target_os = "windows", target_arch = "x86"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("windows-x86");
+#[cfg(all(target_os = "windows", target_arch = "aarch64"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("windows-arm64");
+#[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("mac-os");
+#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("mac-os-arm64");
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("linux");
+#[cfg(all(target_os = "linux", target_arch = "x86"))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = Some("linux-i386");
+#[cfg(not(any(
+    all(target_os = "windows", any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64")),
+    all(target_os = "macos", any(target_arch = "x86_64", target_arch = "aarch64")),
+    all(target_os = "linux", any(target_arch = "x86_64", target_arch = "x86")),
+)))]
+pub const JAVA_RUNTIME_PLATFORM: Option<&str> = None;
+
+// Location of the java executable relative to the root of the runtime
+#[cfg(target_os = "windows")]
+pub const JAVA_EXECUTABLE_PATH: &[&str] = &["bin", "javaw.exe"];
+#[cfg(target_os = "macos")]
+pub const JAVA_EXECUTABLE_PATH: &[&str] = &["jre.bundle", "Contents", "Home", "bin", "java"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const JAVA_EXECUTABLE_PATH: &[&str] = &["bin", "java"];
+
+pub fn get_runtime_executable(component: &str) -> PathBuf {
+    let mut path = get_runtime_dir(component);
+    path.extend(JAVA_EXECUTABLE_PATH);
+    path
+}
+
+///
+/// Gets the java executable of the runtime required by the version, downloading the runtime first if it isn't installed yet.
+/// Falls back to a java installed on the system on platforms Mojang doesn't build runtimes for, e.g. linux on arm64.
+///
+pub fn ensure_runtime_installed(java_version: &JavaVersionInfo) -> LauncherResult<PathBuf> {
+    let executable = get_runtime_executable(java_version.component());
+    // The manifest is only written once every file of the runtime was installed
+    if !executable.is_file() || !get_runtime_manifest_path(java_version.component()).is_file() {
+        match install_runtime(java_version.component()) {
+            Err(LauncherError::JavaRuntimeUnavailable { component }) => {
+                return find_installed_java(java_version).ok_or(LauncherError::JavaRuntimeUnavailable { component });
+            }
+            result => result?,
+        }
+    }
+    Ok(executable)
+}
+
+///
+/// Finds the oldest java on the system that can still run a version requiring `java_version`, since old versions of the game don't always work on newer java
+///
+pub fn find_installed_java(java_version: &JavaVersionInfo) -> Option<PathBuf> {
+    JAVA_INSTALLATIONS.iter().filter(|j| j.major_version() >= java_version.major_version()).min_by_key(|j| j.major_version()).map(|j| j.executable().to_owned())
+}
+
+///
+/// Makes sure the java executable is able to run a version requiring `java_version`, since the game would otherwise crash right away with an `UnsupportedClassVersionError`.
+///
+pub fn check_java_compatibility(executable: &Path, java_version: &JavaVersionInfo) -> LauncherResult<()> {
+    let installation = probe_java_installation(executable).ok_or_else(|| LauncherError::InvalidJavaExecutable(executable.to_owned()))?;
+    if installation.major_version() < java_version.major_version() {
+        return Err(LauncherError::IncompatibleJava { executable: executable.to_owned(), required: java_version.major_version(), found: installation.major_version() });
+    }
+    Ok(())
+}
+
+///
+/// Downloads and verifies every file of the runtime component (e.g. `java-runtime-delta`) for the current platform.
+/// Files that are already present with the correct hash are not downloaded again.
+///
+pub fn install_runtime(component: &str) -> LauncherResult<()> {
+    let unavailable = || LauncherError::JavaRuntimeUnavailable { component: component.to_owned() };
+    let platform = JAVA_RUNTIME_PLATFORM.ok_or_else(unavailable)?;
+
+    let index_text = DEFAULT_DOWNLOADER_CLIENT.get(JAVA_RUNTIME_MANIFEST_URL).send()?.text()?;
+    let index: internal::RuntimeIndex = serde_json::from_str(index_text.as_str())?;
+    let manifest_info = index.get(platform).and_then(|components| components.get(component)).and_then(|runtimes| runtimes.first()).ok_or_else(unavailable)?.manifest;
+
+    let manifest_text = DEFAULT_DOWNLOADER_CLIENT.get(manifest_info.url).send()?.text()?;
+    let manifest_path = get_runtime_manifest_path(component);
+    if !sha1_matches(manifest_text.as_bytes(), &sha1_from_base64_str::<serde_json::Error>(manifest_info.sha1)?) {
+        return Err(LauncherError::FileVerificationFailed(manifest_path));
+    }
+    let manifest: internal::RuntimeManifest = serde_json::from_str(manifest_text.as_str())?;
+
+    let root = get_runtime_dir(component);
+    fs::create_dir_all(&root)?;
+    let mut links = Vec::new();
+    for (path, file_info) in manifest.files {
+        let mut file_path = root.clone();
+        file_path.extend(path.split('/'));
+        match file_info {
+            internal::RuntimeFileInfo::Directory => fs::create_dir_all(&file_path)?,
+            internal::RuntimeFileInfo::File { downloads, executable } => {
+                let file = RuntimeFile::new(
+                    Url::parse(downloads.raw.url).map_err(serde_json::Error::custom)?,
+                    FileHash::Sha1(sha1_from_base64_str::<serde_json::Error>(downloads.raw.sha1)?),
+                    downloads.raw.size,
+                    file_path,
+                );
+                file.download(component)?;
+                file.verify()?;
+                if executable {
+                    set_executable(&file.path)?;
+                }
+            },
+            // Links may point to files that haven't been downloaded yet
+            internal::RuntimeFileInfo::Link { target } => links.push((file_path, target)),
+        }
+    }
+    for (link_path, target) in links {
+        create_link(&link_path, target)?;
+    }
+
+    // Only written at the very end, so that way an interrupted install will be resumed on the next launch
+    fs::write(manifest_path, manifest_text)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_link(path: &Path, target: &str) -> LauncherResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::symlink_metadata(path).is_ok() {
+        fs::remove_file(path)?;
+    }
+    std::os::unix::fs::symlink(target, path)?;
+    Ok(())
+}
+
+// Mojang only uses links in the macOS runtimes
+#[cfg(not(unix))]
+fn create_link(_path: &Path, _target: &str) -> LauncherResult<()> {
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeFile {
+    url: Url,
+    hash: FileHash,
+    size: Option<NonZeroU64>,
+    path: PathBuf,
+}
+
+impl RuntimeFile {
+    pub fn new(url: Url, hash: FileHash, size: Option<NonZeroU64>, path: PathBuf) -> Self {
+        Self { url, hash, size, path }
+    }
+
+    pub fn verify(&self) -> LauncherResult<()> {
+        if sha1_matches(File::open(&self.path)?, self.hash.as_slice()) {
+            Ok(())
+        } else {
+            Err(LauncherError::FileVerificationFailed(self.path.clone()))
+        }
+    }
+}
+
+impl Downloadable for RuntimeFile {
+    fn get_download_url(&self) -> &Url {
+        &self.url
+    }
+
+    fn get_file_path(&self, _component: &str) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn get_hash(&self) -> Option<FileHash> {
+        Some(self.hash)
+    }
+
+    fn get_size(&self) -> Option<NonZeroU64> {
+        self.size
+    }
+}
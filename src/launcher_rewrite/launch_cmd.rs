@@ -1,54 +1,205 @@
-use std::os::windows::process::CommandExt;
-use std::path::Path;
-use std::process::{Child, Command};
-use std::sync::atomic::Ordering;
-use std::{fs, thread};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::fs;
 use std::num::NonZeroUsize;
-use std::sync::LazyLock;
+use std::time::SystemTime;
+use std::sync::{Arc, LazyLock};
 use aho_corasick::AhoCorasick;
+use chrono::Utc;
 use regex::Regex;
-use crate::gui::game_output::open_game_output_window;
-use crate::launcher_rewrite::GAME_INSTANCE_COUNT;
+use crate::launcher_rewrite::authentication::account_data::LoggedInAccount;
+use crate::launcher_rewrite::authentication::CLIENT_ID;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
+use crate::launcher_rewrite::game_output::{GameOutput, OutputSource};
+use crate::launcher_rewrite::instances::{RunningInstance, RUNNING_INSTANCES};
 use crate::launcher_rewrite::installer::Downloadable;
 use crate::launcher_rewrite::launch_properties::Version;
-use crate::launcher_rewrite::path_handler::{DEV_GAME_DIR, get_assets_root, get_bin_path};
+use crate::launcher_rewrite::path_handler::{DEV_GAME_DIR, get_assets_root, get_bin_path, PATH_SEP};
+use crate::launcher_rewrite::hooks::{LaunchHooks, ENV_EXIT_CODE};
+use crate::launcher_rewrite::profiles::{ModLoader, QuickPlay};
+use crate::launcher_rewrite::util::set_executable;
 
 #[cfg(target_os = "windows")]
 pub const CLASSPATH_SEPARATOR: char = ';';
 #[cfg(not(target_os = "windows"))]
 pub const CLASSPATH_SEPARATOR: char = ':';
 
-static MODULE_PATH_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"( -p .+? )|( --module-path .+? )"#).expect("Failed to compile regex!"));
 static MODULE_PATH_JAR_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"[\\/][^\\/]+?\.jar"#).expect("Failed to compile regex!"));
 
+// Shown instead of the access token unless it was explicitly asked for
+const REDACTED_TOKEN: &str = "<access token>";
+
 impl Version {
 
-    pub fn launch(&self, username: &str, uuid: &str, token: &str, resolution: Option<(u32, u32)>, memory: u16, game_dir: &Path) {
-        #[cfg(debug_assertions)]
-        let game_dir = DEV_GAME_DIR.as_path();
-        fs::create_dir_all(game_dir).expect("Failed to create game directory");
-        let mut cmd = Command::new("java");
-        cmd.current_dir(game_dir).raw_arg(get_jvm_args(&self, resolution, memory).as_str()).raw_arg(self.main_class()).raw_arg(get_game_args(&self, username, uuid, token, resolution, game_dir).as_str());
+    pub fn launch(&self, profile_id: Option<u128>, vanilla_id: &str, mod_loader: ModLoader, loader_version: Option<&str>, hooks: &LaunchHooks, java: &Path, account: &LoggedInAccount, resolution: Option<(u32, u32)>, memory: u16, additional_args: &[String], quick_play: Option<&QuickPlay>, game_dir: &Path) -> LauncherResult<Arc<RunningInstance>> {
+        let launch_command = self.launch_command(vanilla_id, mod_loader, loader_version, hooks, java, account, resolution, memory, additional_args, quick_play, game_dir)?;
+        let game_dir = launch_command.current_dir.as_path();
+        fs::create_dir_all(game_dir)?;
+        let mut cmd = launch_command.to_command();
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
         //println!("Main Class: {}", self.main_class());
         //println!("Command: {:?}", cmd);
-        GAME_INSTANCE_COUNT.fetch_add(1, Ordering::SeqCst);
-        let _ = thread::Builder::new().name("Game Process Thread".to_owned()).spawn(move || {
-            // IMPORTANT: Panicking here could cause issues since the active instance count may not be decremented properly!
-            match cmd.status() {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("Failed to start game. {e}");
-                }
+        hooks.run_pre_launch(game_dir, &launch_command.environment)?;
+        let session_start = SystemTime::now();
+        let mut child = cmd.spawn()?;
+
+        let output = Arc::new(GameOutput::new(self.id().to_owned()));
+        let mut output_threads = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            output_threads.extend(output.capture(OutputSource::Stdout, stdout));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            output_threads.extend(output.capture(OutputSource::Stderr, stderr));
+        }
+
+        Ok(RUNNING_INSTANCES.register(profile_id, vanilla_id.to_owned(), loader_version.map(str::to_owned), account, child, output, output_threads, hooks.post_exit_command(game_dir, &launch_command.environment), game_dir.to_owned(), session_start))
+    }
+
+    ///
+    /// Resolves everything needed to start the game without starting it.
+    /// `vanilla_id` is the minecraft version, since the id of a modded version json also names the loader
+    ///
+    pub fn launch_command(&self, vanilla_id: &str, mod_loader: ModLoader, loader_version: Option<&str>, hooks: &LaunchHooks, java: &Path, account: &LoggedInAccount, resolution: Option<(u32, u32)>, memory: u16, additional_args: &[String], quick_play: Option<&QuickPlay>, game_dir: &Path) -> LauncherResult<LaunchCommand> {
+        #[cfg(debug_assertions)]
+        let game_dir = DEV_GAME_DIR.as_path();
+        // Only joining a server has a fallback for old versions, anything else would silently start on the title screen
+        if matches!(quick_play, Some(QuickPlay::Singleplayer(_) | QuickPlay::Realms(_))) && !supports_quick_play(self) {
+            return Err(LauncherError::QuickPlayUnsupported { game_version: vanilla_id.to_owned() });
+        }
+        let mut args: Vec<OsString> = Vec::new();
+        let program = match hooks.wrapper_args()?.split_first() {
+            Some((wrapper, wrapper_args)) => {
+                args.extend(wrapper_args.iter().map(OsString::from));
+                args.push(java.as_os_str().to_owned());
+                OsString::from(wrapper)
+            }
+            None => java.as_os_str().to_owned(),
+        };
+        args.extend(get_jvm_args(&self, account.is_demo_user(), resolution, memory, additional_args));
+        args.push(self.main_class().into());
+        let game_assets = self.game_assets_dir(game_dir)?;
+        args.extend(get_game_args(&self, account, resolution, quick_play, game_dir, &game_assets));
+
+        Ok(LaunchCommand {
+            program,
+            args,
+            environment: hooks.environment(game_dir, vanilla_id, mod_loader, loader_version),
+            current_dir: game_dir.to_owned(),
+            pre_launch_hook: hooks.pre_launch().map(str::to_owned),
+            post_exit_hook: hooks.post_exit().map(str::to_owned),
+            access_token: account.minecraft_token().to_owned(),
+        })
+    }
+
+}
+
+///
+/// The fully resolved command that starts the game, for launching or exporting
+///
+#[derive(Debug, Clone)]
+pub struct LaunchCommand {
+    program: OsString,
+    args: Vec<OsString>,
+    environment: Vec<(&'static str, OsString)>,
+    current_dir: PathBuf,
+    pre_launch_hook: Option<String>,
+    post_exit_hook: Option<String>,
+    access_token: String,
+}
+
+impl LaunchCommand {
+    pub fn to_command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.current_dir(&self.current_dir).envs(self.environment.iter().cloned()).args(&self.args);
+        cmd
+    }
+
+    ///
+    /// The program followed by all of its arguments, with the access token replaced unless `show_token` is set
+    ///
+    pub fn argv(&self, show_token: bool) -> Vec<String> {
+        std::iter::once(&self.program).chain(self.args.iter()).map(|arg| {
+            let arg = arg.to_string_lossy();
+            if show_token || self.access_token.is_empty() {
+                arg.into_owned()
+            } else {
+                arg.replace(self.access_token.as_str(), REDACTED_TOKEN)
             }
-            GAME_INSTANCE_COUNT.fetch_sub(1, Ordering::SeqCst);
-        });
+        }).collect()
+    }
+
+    pub fn to_display_string(&self, show_token: bool) -> String {
+        self.argv(show_token).iter().map(|arg| quote_shell_arg(arg)).intersperse(" ".to_owned()).collect()
+    }
+
+    ///
+    /// A script that runs the hooks and the game the same way the launcher would
+    ///
+    #[cfg(not(target_os = "windows"))]
+    pub fn to_shell_script(&self, show_token: bool) -> String {
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str(&format!("cd {} || exit 1\n", quote_shell_arg(&self.current_dir.to_string_lossy())));
+        for (name, value) in &self.environment {
+            script.push_str(&format!("export {}={}\n", name, quote_shell_arg(&value.to_string_lossy())));
+        }
+        if let Some(hook) = &self.pre_launch_hook {
+            script.push_str(&format!("sh -c {} || exit 1\n", quote_shell_arg(hook)));
+        }
+        script.push_str(&self.to_display_string(show_token));
+        script.push('\n');
+        if let Some(hook) = &self.post_exit_hook {
+            script.push_str(&format!("{}=$? sh -c {}\n", ENV_EXIT_CODE, quote_shell_arg(hook)));
+        }
+        script
     }
 
+    ///
+    /// A script that runs the hooks and the game the same way the launcher would
+    ///
+    #[cfg(target_os = "windows")]
+    pub fn to_shell_script(&self, show_token: bool) -> String {
+        let mut script = String::from("@echo off\r\n");
+        script.push_str(&format!("cd /d {}\r\n", quote_shell_arg(&self.current_dir.to_string_lossy())));
+        for (name, value) in &self.environment {
+            script.push_str(&format!("set \"{}={}\"\r\n", name, value.to_string_lossy().replace('%', "%%")));
+        }
+        if let Some(hook) = &self.pre_launch_hook {
+            script.push_str(&format!("cmd /C {} || exit /b 1\r\n", quote_shell_arg(hook)));
+        }
+        script.push_str(&self.to_display_string(show_token));
+        script.push_str("\r\n");
+        if let Some(hook) = &self.post_exit_hook {
+            script.push_str(&format!("set \"{}=%ERRORLEVEL%\"\r\ncmd /C {}\r\n", ENV_EXIT_CODE, quote_shell_arg(hook)));
+        }
+        script
+    }
+
+    pub fn write_shell_script(&self, path: &Path, show_token: bool) -> LauncherResult<()> {
+        fs::write(path, self.to_shell_script(show_token))?;
+        set_executable(path)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn quote_shell_arg(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c)) {
+        return arg.to_owned();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+#[cfg(target_os = "windows")]
+fn quote_shell_arg(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./\\:=@+,".contains(c)) {
+        return arg.to_owned();
+    }
+    format!("\"{}\"", arg.replace('%', "%%").replace('"', "\"\""))
 }
 
 fn get_classpath(version: &Version) -> String {
     let mut classpath = String::new();
-    version.libs().iter().for_each(|lib| {
+    version.libs().iter().filter(|lib| lib.on_classpath()).for_each(|lib| {
         classpath.push_str(lib.get_file_path(version.game_version()).to_str().unwrap());
         classpath.push(CLASSPATH_SEPARATOR);
     });
@@ -59,51 +210,76 @@ fn get_classpath(version: &Version) -> String {
     classpath
 }
 
-fn get_game_args(version: &Version, username: &str, uuid: &str, token: &str, resolution: Option<(u32, u32)>, game_dir: &Path) -> String {
+fn get_game_args(version: &Version, account: &LoggedInAccount, resolution: Option<(u32, u32)>, quick_play: Option<&QuickPlay>, game_dir: &Path, game_assets: &Path) -> Vec<OsString> {
 
     #[cfg(debug_assertions)]
-    let binding = DEV_GAME_DIR.to_string_lossy();
-    #[cfg(debug_assertions)]
+    let game_dir = DEV_GAME_DIR.as_path();
+    let binding = game_dir.to_string_lossy();
     let game_dir = binding.as_ref();
-    #[cfg(not(debug_assertions))]
-    let game_dir = game_dir.to_string_lossy().as_ref();
 
+    let username = account.minecraft_account_info().name();
+    let uuid = account.minecraft_account_info().id();
+    let token = account.minecraft_token();
+    let xuid = account.xuid().unwrap_or_default();
 
     let binding = get_assets_root();
     let assets_root = binding.to_str().unwrap();
-    let assets_name = version.assets().id().rsplit_once('.').map(|split| split.0).unwrap_or(version.assets().id());
+    let assets_name = version.assets().name();
+    let binding = game_assets.to_string_lossy();
+    let game_assets = binding.as_ref();
 
     let has_custom_resolution = resolution.is_some();
     let disp = resolution.map(|res| (res.0.to_string(), res.1.to_string()));
     let (width, height) = disp.as_ref().map(|res| (res.0.as_str(), res.1.as_str())).unwrap_or(("1920", "1080"));
-    let quick_play = false;
-    let quick_play_singleplayer = false;
-    let quick_play_multiplayer = false;
-    let quick_play_realms = false;
-    let owns_game = true;
+    let has_quick_play_support = quick_play.is_some();
+    let quick_play_singleplayer = matches!(quick_play, Some(QuickPlay::Singleplayer(_)));
+    let quick_play_multiplayer = matches!(quick_play, Some(QuickPlay::Multiplayer(_)));
+    let quick_play_realms = matches!(quick_play, Some(QuickPlay::Realms(_)));
+    let quick_play_target = quick_play.map(|q| q.target()).unwrap_or_default();
+    // Relative to the game directory, the game writes the result of the quick play attempt there
+    let quick_play_path = format!("quickPlay{0}java{0}{1}.json", PATH_SEP, Utc::now().timestamp_millis());
 
-    let unformatted: String = version.arguments().game_args().iter().filter(|a| a.matches(!owns_game, has_custom_resolution, quick_play, quick_play_singleplayer, quick_play_multiplayer, quick_play_realms)).map(|a| a.values()).flatten().map(|s| s.as_str()).intersperse(" ").collect();
-    const PLACEHOLDERS: &[&str] = &["${auth_player_name}", "${version_name}", "${game_directory}", "${assets_root}", "${assets_index_name}", "${auth_uuid}", "${auth_access_token}", "${clientid}", "${auth_xuid}", "${user_type}", "${version_type}", "${resolution_width}", "${resolution_height}", "${quickPlayPath}", "${quickPlaySingleplayer}", "${quickPlayMultiplayer}", "${quickPlayRealms}"];
-    let replace = [username, version.game_version(), game_dir, assets_root, assets_name, uuid, token, "telemetry", "asdf", "msa", version.version_type().as_str(), width, height, "placeholder", "placeholder", "placeholder", "placeholder"];
+    // Legacy versions pass the session as `token:<access token>:<uuid>`
+    let auth_session = format!("token:{}:{}", token, uuid);
+
+    const PLACEHOLDERS: &[&str] = &["${auth_player_name}", "${version_name}", "${game_directory}", "${assets_root}", "${assets_index_name}", "${auth_uuid}", "${auth_access_token}", "${clientid}", "${auth_xuid}", "${user_type}", "${version_type}", "${resolution_width}", "${resolution_height}", "${quickPlayPath}", "${quickPlaySingleplayer}", "${quickPlayMultiplayer}", "${quickPlayRealms}", "${auth_session}", "${user_properties}", "${game_assets}"];
+    let replace = [username, version.game_version(), game_dir, assets_root, assets_name, uuid, token, CLIENT_ID.as_str(), xuid, account.user_type(), version.version_type().as_str(), width, height, quick_play_path.as_str(), quick_play_target, quick_play_target, quick_play_target, auth_session.as_str(), "{}", game_assets];
     let ac = AhoCorasick::new(PLACEHOLDERS).unwrap();
-    let formatted = ac.replace_all(unformatted.as_str(), &replace);
-    formatted
+    let mut args: Vec<OsString> = version.arguments().game_args().iter()
+        .filter(|a| a.matches(account.is_demo_user(), has_custom_resolution, has_quick_play_support, quick_play_singleplayer, quick_play_multiplayer, quick_play_realms))
+        .flat_map(|a| a.values())
+        .map(|arg| OsString::from(ac.replace_all(arg.as_str(), &replace)))
+        .collect();
+
+    // Versions before 1.20 don't support quick play, but can still join a server directly
+    if let Some(QuickPlay::Multiplayer(server)) = quick_play {
+        if !supports_quick_play(version) {
+            let (host, port) = match server.rsplit_once(':') {
+                Some((host, port)) if port.parse::<u16>().is_ok() => (host, port),
+                _ => (server.as_str(), "25565"),
+            };
+            args.extend(["--server", host, "--port", port].map(OsString::from));
+        }
+    }
+    args
 }
 
-fn get_jvm_args(version: &Version, resolution: Option<(u32, u32)>, memory: u16) -> String {
+fn supports_quick_play(version: &Version) -> bool {
+    version.arguments().game_args().iter().flat_map(|a| a.values()).any(|v| v.contains("${quickPlayMultiplayer}"))
+}
+
+fn get_jvm_args(version: &Version, is_demo_user: bool, resolution: Option<(u32, u32)>, memory: u16, additional_args: &[String]) -> Vec<OsString> {
 
     let has_custom_resolution = resolution.is_some();
     let quick_play = false;
     let quick_play_singleplayer = false;
     let quick_play_multiplayer = false;
     let quick_play_realms = false;
-    let owns_game = true;
 
     let natives_dir = get_bin_path(version.game_version());
     let classpath = get_classpath(version);
-    let log_config_file_path = version.log_info().get_file_path(version.game_version());
+    let log_config_file_path = version.log_info().map(|l| l.get_file_path(version.game_version())).unwrap_or_default();
 
-    let unformatted: String = version.arguments().jvm_args().iter().filter(|a| a.matches(!owns_game, has_custom_resolution, quick_play, quick_play_singleplayer, quick_play_multiplayer, quick_play_realms)).map(|a| a.values()).flatten().map(|s| s.as_str()).intersperse(" ").collect();
     const PLACEHOLDERS: &[&str] = &["${natives_directory}", "${launcher_name}", "${launcher_version}", "${classpath}", "${logging_path}", "${version_name}", "${classpath_separator}", "${library_directory}"];
     let binding = natives_dir.to_string_lossy();
     let binding2 = log_config_file_path.to_string_lossy();
@@ -113,28 +289,116 @@ fn get_jvm_args(version: &Version, resolution: Option<(u32, u32)>, memory: u16)
     let classpath_separator: &str = CLASSPATH_SEPARATOR.encode_utf8(&mut cp_separator_buf);
     let replace = [binding.as_ref(), env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), classpath.as_str(), binding2.as_ref(), version.game_version(), classpath_separator, bin_dir_str.as_ref()];
     let ac = AhoCorasick::new(PLACEHOLDERS).unwrap();
-    let mut formatted = ac.replace_all(unformatted.as_str(), &replace);
+    let mut formatted: Vec<String> = version.arguments().jvm_args().iter()
+        .filter(|a| a.matches(is_demo_user, has_custom_resolution, quick_play, quick_play_singleplayer, quick_play_multiplayer, quick_play_realms))
+        .flat_map(|a| a.values())
+        .map(|arg| ac.replace_all(arg.as_str(), &replace))
+        .collect();
 
     // Module path madness because neoforge devs have cacti up their asses
-    let module_path_indices = MODULE_PATH_REGEX.find(formatted.as_str());
-    if let Some(module_path_match) = module_path_indices {
-        let module_path_str = module_path_match.as_str();
-        let module_jars = MODULE_PATH_JAR_REGEX.find_iter(module_path_str);
-        let mut module_path = String::with_capacity(1024);
-        module_path.push_str(" -p ");
-        module_jars.for_each(|jar_name| {
-            module_path.push_str(bin_dir_str.as_ref());
-            module_path.push_str(jar_name.as_str());
-            module_path.push_str(classpath_separator);
-        });
-        // remove trailing `;` is this necessary?
-        if !module_path.is_empty() {
-            module_path.pop();
-            module_path.push(' ');
+    // All libraries are in the bin folder, so point every module path jar there instead
+    if let Some(index) = formatted.iter().position(|a| a == "-p" || a == "--module-path") {
+        if let Some(module_path) = formatted.get_mut(index + 1) {
+            *module_path = MODULE_PATH_JAR_REGEX.find_iter(module_path.as_str()).map(|jar_name| format!("{}{}", bin_dir_str, jar_name.as_str())).intersperse(classpath_separator.to_owned()).collect();
         }
-        // TODO optimize
-        formatted = formatted.replace(module_path_str, module_path.as_str());
     }
-    formatted.push_str(format!(" -Xms{0}G -Xmx{0}G", memory).as_str());
-    formatted
-}
\ No newline at end of file
+    formatted.push(format!("-Xms{}G", memory));
+    formatted.push(format!("-Xmx{}G", memory));
+    // Added last so that way they can override the memory settings
+    formatted.extend(additional_args.iter().cloned());
+    formatted.into_iter().map(OsString::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+    use crate::launcher_rewrite::authentication::account_data::LoggedInAccount;
+    use crate::launcher_rewrite::error::LauncherError;
+    use crate::launcher_rewrite::hooks::LaunchHooks;
+    use crate::launcher_rewrite::launch_cmd::{get_game_args, get_jvm_args, quote_shell_arg, LaunchCommand, REDACTED_TOKEN};
+    use crate::launcher_rewrite::launch_properties::Version;
+    use crate::launcher_rewrite::path_handler::get_virtual_assets_dir;
+    use crate::launcher_rewrite::profiles::{ModLoader, QuickPlay};
+
+    const LEGACY_VERSION_JSON: &str = r#"{
+        "id": "1.7.10",
+        "type": "release",
+        "mainClass": "net.minecraft.client.main.Main",
+        "minecraftArguments": "--username ${auth_player_name} --session ${auth_session} --assetsDir ${game_assets}",
+        "assetIndex": {"id": "1.7.10", "url": "https://example.com/1.7.10.json"},
+        "downloads": {"client": {"url": "https://example.com/client.jar"}, "server": {"url": "https://example.com/server.jar"}}
+    }"#;
+
+    const ACCOUNT_JSON: &str = r#"{
+        "microsoft_token_info": {"access_token": "ms", "token_type": "bearer", "expires_in": 86400, "scope": "scope", "refresh_token": "refresh"},
+        "xbox_live_token_info": {"IssueInstant": "", "NotAfter": "", "Token": "xbox", "DisplayClaims": {"xui": [{"uhs": "hash"}]}},
+        "xsts_token_info": {"IssueInstant": "", "NotAfter": "", "Token": "xsts", "DisplayClaims": {"xui": [{"uhs": "hash"}]}},
+        "minecraft_token_info": {"username": "user", "access_token": "token", "token_type": "Bearer", "expires_in": 86400},
+        "name": "Player", "id": "uuid", "skins": [], "capes": []
+    }"#;
+
+    #[test]
+    fn legacy_arguments_are_filled_in() {
+        let version: Version = serde_json::from_str(LEGACY_VERSION_JSON).unwrap();
+        let account: LoggedInAccount = serde_json::from_str(ACCOUNT_JSON).unwrap();
+
+        let jvm_args = get_jvm_args(&version, false, None, 2048, &[]);
+        assert!(jvm_args.iter().any(|a| a.to_string_lossy().starts_with("-Djava.library.path=")));
+        assert!(jvm_args.contains(&OsString::from("-cp")));
+
+        let game_args = get_game_args(&version, &account, None, None, Path::new("game"), &get_virtual_assets_dir("legacy"));
+        let expected = [OsString::from("--username"), OsString::from("Player"), OsString::from("--session"), OsString::from("token:token:uuid"), OsString::from("--assetsDir"), get_virtual_assets_dir("legacy").into_os_string()];
+        assert_eq!(game_args, expected);
+    }
+
+    #[test]
+    fn old_versions_reject_world_quick_play() {
+        let version: Version = serde_json::from_str(LEGACY_VERSION_JSON).unwrap();
+        let account: LoggedInAccount = serde_json::from_str(ACCOUNT_JSON).unwrap();
+        let quick_play = QuickPlay::Singleplayer(String::from("world"));
+        let result = version.launch_command("1.7.10", ModLoader::Vanilla, None, &LaunchHooks::default(), Path::new("java"), &account, None, 2, &[], Some(&quick_play), Path::new("game"));
+        assert!(matches!(result, Err(LauncherError::QuickPlayUnsupported { .. })));
+    }
+
+    fn command_with_token(token: &str) -> LaunchCommand {
+        LaunchCommand {
+            program: OsString::from("java"),
+            args: [format!("token:{token}:uuid"), String::from("--accessToken"), token.to_owned(), String::from("--username"), String::from("It's me")].into_iter().map(OsString::from).collect(),
+            environment: vec![("LAUNCHER_PROFILE_NAME", OsString::from("My Profile"))],
+            current_dir: PathBuf::from("game dir"),
+            pre_launch_hook: Some(String::from("echo 'starting'")),
+            post_exit_hook: None,
+            access_token: token.to_owned(),
+        }
+    }
+
+    #[test]
+    fn access_token_is_redacted() {
+        let command = command_with_token("secret");
+
+        let argv = command.argv(false);
+        assert_eq!(argv[1], format!("token:{REDACTED_TOKEN}:uuid"));
+        assert_eq!(argv[3], REDACTED_TOKEN);
+        assert!(!command.to_display_string(false).contains("secret"));
+        assert!(!command.to_shell_script(false).contains("secret"));
+
+        assert_eq!(command.argv(true)[1], "token:secret:uuid");
+        assert!(command.to_display_string(true).contains("secret"));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn shell_args_are_quoted() {
+        assert_eq!(quote_shell_arg("-Xmx2G"), "-Xmx2G");
+        assert_eq!(quote_shell_arg(""), "''");
+        assert_eq!(quote_shell_arg("game dir"), "'game dir'");
+        assert_eq!(quote_shell_arg("It's me"), r"'It'\''s me'");
+
+        let script = command_with_token("secret").to_shell_script(false);
+        assert!(script.starts_with("#!/bin/sh\ncd 'game dir' || exit 1\n"));
+        assert!(script.contains("export LAUNCHER_PROFILE_NAME='My Profile'\n"));
+        assert!(script.contains(r"sh -c 'echo '\''starting'\''' || exit 1"));
+        assert!(script.contains(r"--username 'It'\''s me'"));
+    }
+}
@@ -15,7 +15,10 @@ use crate::launcher_rewrite::version_type::VersionType;
 #[serde(rename_all = "camelCase")]
 pub (in crate::launcher_rewrite::launch_properties) struct ClientJson<'file> {
     pub inherits_from: Option<&'file str>,
+    // Versions before 1.13 only have `minecraftArguments` instead
+    #[serde(borrow, default)]
     pub arguments: Arguments<'file>,
+    pub minecraft_arguments: Option<&'file str>,
     pub asset_index: Option<AssetIndexInfo<'file>>,
     pub assets: Option<&'file str>,
     pub compliance_level: Option<u8>,
@@ -37,7 +40,14 @@ pub (in crate::launcher_rewrite::launch_properties) struct ClientJson<'file> {
     pub release_type: Option<VersionType>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// Only the fields needed to walk the inheritance chain, so that way parents aren't fully deserialized twice
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub (in crate::launcher_rewrite::launch_properties) struct InheritanceInfo {
+    pub inherits_from: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub (in crate::launcher_rewrite::launch_properties) struct Arguments<'file> {
     #[serde(borrow, default)]
     pub game: Vec<Arg<'file>>,
@@ -119,8 +129,8 @@ impl<'file> MainDownloads<'file> {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub (in crate::launcher_rewrite::launch_properties) struct JavaInfo<'file> {
-    component: &'file str,
-    major_version: u8,
+    pub component: &'file str,
+    pub major_version: u8,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -151,6 +161,11 @@ pub (in crate::launcher_rewrite::launch_properties) struct Library<'file> {
     pub name: &'file str,
     #[serde(default)]
     pub rules: Rules<'file>,
+    // Maps an os name to the classifier holding its natives, e.g. `"linux": "natives-linux"`
+    #[serde(borrow, default)]
+    pub natives: HashMap<&'file str, &'file str>,
+    #[serde(borrow)]
+    pub extract: Option<ExtractRules<'file>>,
 }
 
 impl<'file> Library<'file> {
@@ -187,8 +202,17 @@ pub (in crate::launcher_rewrite::launch_properties) enum LibraryFormat<'file> {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub (in crate::launcher_rewrite::launch_properties) struct LibDownload<'file> {
+    // Natives-only libraries from older versions just have classifiers
     #[serde(borrow)]
-    pub artifact: ArtifactDownload<'file>,
+    pub artifact: Option<ArtifactDownload<'file>>,
+    #[serde(borrow, default)]
+    pub classifiers: HashMap<&'file str, ArtifactDownload<'file>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub (in crate::launcher_rewrite::launch_properties) struct ExtractRules<'file> {
+    #[serde(borrow, default)]
+    pub exclude: Vec<&'file str>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -0,0 +1,133 @@
+use reqwest::Url;
+use serde::de::Error;
+use crate::launcher_rewrite::launch_properties::{Architecture, LibraryInfo, Os};
+use crate::launcher_rewrite::urls::MAVEN_CENTRAL_URL;
+
+///
+/// Swaps a library of the version json for a build from Maven Central on platforms Mojang doesn't ship natives for
+///
+#[derive(Debug, Clone, Copy)]
+pub struct LibraryOverride {
+    os: Os,
+    arch: Architecture,
+    group_id: &'static str,
+    // Library versions the override applies to
+    versions: &'static [&'static str],
+    // The version to use instead, None keeps the version from the version json
+    replacement_version: Option<&'static str>,
+    natives_classifier: &'static str,
+}
+
+// Mojang only ships x86_64 LWJGL 3 natives for linux. LWJGL before 3.3 has no arm64 builds, so those versions are bumped to 3.3.1.
+pub const LIBRARY_OVERRIDES: &[LibraryOverride] = &[
+    LibraryOverride {
+        os: Os::Linux,
+        arch: Architecture::Aarch64,
+        group_id: "org.lwjgl",
+        versions: &["3.1.6", "3.2.1", "3.2.2"],
+        replacement_version: Some("3.3.1"),
+        natives_classifier: "natives-linux-arm64",
+    },
+    LibraryOverride {
+        os: Os::Linux,
+        arch: Architecture::Aarch64,
+        group_id: "org.lwjgl",
+        versions: &["3.3.1", "3.3.2", "3.3.3"],
+        replacement_version: None,
+        natives_classifier: "natives-linux-arm64",
+    },
+];
+
+impl LibraryOverride {
+    fn applies_to(&self, os: Os, arch: Option<Architecture>, group_id: &str, version: &str) -> bool {
+        self.os == os && Some(self.arch) == arch && self.group_id == group_id && self.versions.contains(&version)
+    }
+
+    fn library<E: Error>(&self, group_id: &str, artifact_id: &str, version: &str, classifier: Option<&str>) -> Result<LibraryInfo, E> {
+        let version = self.replacement_version.unwrap_or(version);
+        let file_name = match classifier {
+            None => format!("{}-{}.jar", artifact_id, version),
+            Some(classifier) => format!("{}-{}-{}.jar", artifact_id, version, classifier),
+        };
+        let url = Url::parse(format!("{}{}/{}/{}/{}", MAVEN_CENTRAL_URL, group_id.replace('.', "/"), artifact_id, version, file_name).as_str()).map_err(E::custom)?;
+        let name = match classifier {
+            None => format!("{}:{}:{}", group_id, artifact_id, version),
+            Some(classifier) => format!("{}:{}:{}:{}", group_id, artifact_id, version, classifier),
+        };
+        // Maven Central doesn't list hashes in the version json, so the jars can't be verified
+        Ok(LibraryInfo::new(url, None, None, file_name, name))
+    }
+}
+
+///
+/// Maps a library through the override table. Returns None if no override applies, otherwise the libraries to use instead.
+///
+/// `name` is the full maven name of the library, `has_natives` whether it has a natives classifier for the current os
+///
+pub(super) fn override_library<E: Error>(name: &str, has_natives: bool) -> Result<Option<Vec<LibraryInfo>>, E> {
+    override_library_for(name, has_natives, Os::current(), Architecture::current())
+}
+
+fn override_library_for<E: Error>(name: &str, has_natives: bool, os: Os, arch: Option<Architecture>) -> Result<Option<Vec<LibraryInfo>>, E> {
+    let mut parts = name.split(':');
+    let (Some(group_id), Some(artifact_id), Some(version)) = (parts.next(), parts.next(), parts.next()) else { return Ok(None) };
+    let classifier = parts.next();
+    let Some(library_override) = LIBRARY_OVERRIDES.iter().find(|o| o.applies_to(os, arch, group_id, version)) else { return Ok(None) };
+
+    match classifier {
+        // Newer versions list every natives jar as its own library, only the one for the current os gets replaced
+        Some(classifier) if classifier == format!("natives-{}", os.as_str()) => {
+            Ok(Some(vec![library_override.library(group_id, artifact_id, version, Some(library_override.natives_classifier))?.with_natives(Vec::new(), true)]))
+        }
+        Some(_) => Ok(None),
+        None => {
+            let mut libraries = vec![library_override.library(group_id, artifact_id, version, None)?];
+            if has_natives {
+                libraries.push(library_override.library(group_id, artifact_id, version, Some(library_override.natives_classifier))?.with_natives(Vec::new(), false));
+            }
+            Ok(Some(libraries))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::launcher_rewrite::launch_properties::library_overrides::override_library_for;
+    use crate::launcher_rewrite::launch_properties::{Architecture, LibraryInfo, Os};
+
+    fn override_on_arm64(name: &str, has_natives: bool) -> Option<Vec<LibraryInfo>> {
+        override_library_for::<serde_json::Error>(name, has_natives, Os::Linux, Some(Architecture::Aarch64)).unwrap()
+    }
+
+    #[test]
+    fn old_lwjgl_is_replaced_with_natives() {
+        let libs = override_on_arm64("org.lwjgl:lwjgl:3.2.2", true).unwrap();
+        let names: Vec<&str> = libs.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["org.lwjgl:lwjgl:3.3.1", "org.lwjgl:lwjgl:3.3.1:natives-linux-arm64"]);
+        assert_eq!(libs[1].download_url.as_str(), "https://repo1.maven.org/maven2/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux-arm64.jar");
+        assert!(libs[0].extract_exclude().is_none());
+        assert!(libs[1].extract_exclude().is_some() && !libs[1].on_classpath());
+
+        let libs = override_on_arm64("org.lwjgl:lwjgl-glfw:3.2.2", false).unwrap();
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].name, "org.lwjgl:lwjgl-glfw:3.3.1");
+    }
+
+    #[test]
+    fn only_the_linux_natives_classifier_is_replaced() {
+        let libs = override_on_arm64("org.lwjgl:lwjgl:3.3.1:natives-linux", false).unwrap();
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].name, "org.lwjgl:lwjgl:3.3.1:natives-linux-arm64");
+        assert!(libs[0].on_classpath());
+        assert!(override_on_arm64("org.lwjgl:lwjgl:3.3.1:natives-windows", false).is_none());
+    }
+
+    #[test]
+    fn other_libraries_and_platforms_are_left_alone() {
+        assert!(override_on_arm64("org.lwjgl.lwjgl:lwjgl:2.9.4-nightly-20150209", true).is_none());
+        assert!(override_on_arm64("org.lwjgl:lwjgl:3.3.4", true).is_none());
+        assert!(override_on_arm64("com.mojang:brigadier:1.0.18", false).is_none());
+        assert!(override_library_for::<serde_json::Error>("org.lwjgl:lwjgl:3.2.2", true, Os::Linux, Some(Architecture::X64)).unwrap().is_none());
+        assert!(override_library_for::<serde_json::Error>("org.lwjgl:lwjgl:3.2.2", true, Os::Osx, Some(Architecture::Aarch64)).unwrap().is_none());
+    }
+}
@@ -1,10 +1,13 @@
 mod internal;
+pub mod library_overrides;
 
-use crate::launcher_rewrite::assets::AssetsIndex;
-use crate::launcher_rewrite::jar_utils::extractor::extract_dlls_from_jar;
+use crate::launcher_rewrite::assets::{AssetsIndex, RESOURCES_FOLDER};
+use crate::launcher_rewrite::jar_utils::extractor::{extract_natives_from_jar, NATIVE_JARS_SUFFIX};
 use crate::launcher_rewrite::installer::Downloadable;
-use crate::launcher_rewrite::launch_properties::internal::{Arg, AssetIndexInfo, ClientJson, LibraryFormat, LoggingInfo, RuleAction};
-use crate::launcher_rewrite::path_handler::{get_assets_index_dir, get_bin_path, get_log_configs_folder, get_vanilla_client_json_path, BIN_PATH};
+use crate::launcher_rewrite::launch_properties::library_overrides::override_library;
+use crate::launcher_rewrite::launch_properties::internal::{Arg, AssetIndexInfo, ClientJson, InheritanceInfo, LibraryFormat, LoggingInfo, RuleAction};
+use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
+use crate::launcher_rewrite::path_handler::{get_assets_index_dir, get_assets_root, get_virtual_assets_dir, get_bin_path, get_custom_client_json_path, get_log_configs_folder, get_vanilla_client_json_path, BIN_PATH};
 use crate::launcher_rewrite::profiles::ModLoader;
 use crate::launcher_rewrite::util::hash;
 use crate::launcher_rewrite::util::hash::{sha1_from_base64_str, FileHash};
@@ -14,16 +17,17 @@ use chrono::{DateTime, Utc};
 use reqwest::Url;
 use serde::de::{Error, MapAccess, Unexpected, Visitor};
 use serde::{de, Deserialize, Deserializer};
+use std::collections::HashSet;
 use std::env::consts::{ARCH, OS};
 use std::iter::Map;
 use std::num::NonZeroU64;
 use std::ops::BitAnd;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::slice::Iter;
 use std::str::FromStr;
 use std::{fs, vec};
 use regex::Regex;
-use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
 
 #[derive(Debug, Clone)]
 pub struct Version {
@@ -35,7 +39,8 @@ pub struct Version {
     arguments: Arguments,
     libs: Vec<LibraryInfo>,
     assets: AssetsIndexInfo,
-    log_info: LogConfigInfo,
+    log_info: Option<LogConfigInfo>,
+    java_version: JavaVersionInfo,
 }
 
 impl Version {
@@ -53,13 +58,15 @@ impl Version {
         assets_index.download_all(version_name)?;
 
         // Log configs
-        self.log_info.download(version_name)?;
+        if let Some(log_info) = &self.log_info {
+            log_info.download(version_name)?;
+        }
 
-        // Extract dlls
+        // Extract natives
         let extract_path = get_bin_path(version_name);
-        self.libs.iter().map(|lib| {
+        self.libs.iter().filter_map(|lib| lib.extract_exclude().map(|exclude| (lib, exclude))).map(|(lib, exclude)| {
             let path = lib.get_file_path(version_name);
-            extract_dlls_from_jar(&extract_path, &path)?;
+            extract_natives_from_jar(&extract_path, &path, exclude)?;
             Ok(())
         }).collect::<Result<Vec<()>, LauncherError>>()?;
         Ok(())
@@ -97,8 +104,29 @@ impl Version {
         &self.assets
     }
 
-    pub fn log_info(&self) -> &LogConfigInfo {
-        &self.log_info
+    pub fn log_info(&self) -> Option<&LogConfigInfo> {
+        self.log_info.as_ref()
+    }
+
+    pub fn java_version(&self) -> &JavaVersionInfo {
+        &self.java_version
+    }
+
+    ///
+    /// The folder passed as `${game_assets}`. Versions before 1.7.3 can't read the hashed objects folder, so the assets are put where they expect them first.
+    ///
+    pub fn game_assets_dir(&self, game_dir: &Path) -> LauncherResult<PathBuf> {
+        let index_file = fs::read_to_string(self.assets.get_file_path(self.game_version.as_str()))?;
+        let assets_index: AssetsIndex = serde_json::from_str(index_file.as_str())?;
+        let dir = if assets_index.map_to_resources() {
+            game_dir.join(RESOURCES_FOLDER)
+        } else if assets_index.is_virtual() {
+            get_virtual_assets_dir(self.assets.name())
+        } else {
+            return Ok(get_assets_root());
+        };
+        assets_index.copy_objects_to(&dir)?;
+        Ok(dir)
     }
 }
 
@@ -108,42 +136,59 @@ impl<'de> Deserialize<'de> for Version {
         D: Deserializer<'de>,
     {
         let json: ClientJson = ClientJson::deserialize(deserializer)?;
-        let mut file: Option<String> = None;
-        let mut inherited: Option<ClientJson> = None;
 
-        if let Some(inheritance) = json.inherits_from {
-            // TODO think of a good way to not deserialize some files twice
-            // TODO download inherited file if needed
-
-            file = Some(fs::read_to_string(get_vanilla_client_json_path(inheritance, ModLoader::Vanilla, "")).map_err(|e| Error::custom(e))?); // Only allow inheriting from vanilla for now
-            inherited = Some(serde_json::from_str(file.as_ref().unwrap().as_str()).map_err(|e| Error::custom(e))?);
-            if inherited.as_ref().unwrap().inherits_from.is_some() {
-                return Err(Error::custom(">2-level client json inheritance is currently unsupported."));
-            }
+        // Read every json in the inheritance chain first, so that way they all outlive the borrowed `ClientJson`s below
+        let parent_files = read_inheritance_chain(json.version_id, json.inherits_from)?;
+        let mut chain: Vec<ClientJson> = Vec::with_capacity(parent_files.len() + 1);
+        chain.push(json);
+        for file in &parent_files {
+            chain.push(serde_json::from_str(file.as_str()).map_err(Error::custom)?);
         }
+        // `chain` is ordered from the most derived json to the base (vanilla) json
+        let json = &chain[0];
+        let base = &chain[chain.len() - 1];
 
         let id: String = json.version_id.into();
-        let game_version: String = if let Some(i) = &inherited { i.version_id.into() } else { json.version_id.into() };
+        let game_version: String = base.version_id.into();
         if json.time != json.release_time {
             return Err(Error::custom("Time and release time do not match!"));
         }
         let time = json.time;
-        let main_class: String = first_or_second_or_missing(json.main_class.map(|s| String::from(s)), inherited.as_ref(), |j| Ok(j.main_class.map(|s| String::from(s))), "mainClass")?; //json.main_class.into();
-        let version_type = first_or_second_or_missing(json.release_type, inherited.as_ref(), |j| Ok(j.release_type), "type")?;
+        let main_class: String = first_in_chain(&chain, |j| Ok(j.main_class.map(|s| String::from(s))), "mainClass")?;
+        let version_type = first_in_chain(&chain, |j| Ok(j.release_type), "type")?;
+
+        // Arguments of parents come first so that way children can append to them
+        let mut game_args: Vec<Argument> = Vec::new();
+        let mut jvm_args: Vec<Argument> = Vec::new();
+
+        // Legacy jsons replace the whole argument string of their parents instead of appending to it, and have no jvm arguments at all
+        if let Some(legacy_args) = chain.iter().find_map(|j| j.minecraft_arguments) {
+            jvm_args.extend(legacy_jvm_args());
+            game_args.push(Argument::without_rules(legacy_args.split_whitespace().map(String::from).collect()));
+            game_args.push(Argument::with_rules(vec![String::from("--demo")], vec![Rule::new(RuleAction::Allow, vec![RuleCondition::IsDemoUser(true)])]));
+        }
 
-        let (game_args_internal, jvm_args_internal) = json.arguments.into_parts();
-        // TODO optimize to reduce allocations
-        let a1 = Argument::without_rules(map_unconditional_args(game_args_internal.iter()));
-        let mut game_args: Vec<Argument> = map_args(game_args_internal)?;
-        game_args.push(a1);
+        for j in chain.iter().rev() {
+            // TODO optimize to reduce allocations
+            let a1 = Argument::without_rules(map_unconditional_args(j.arguments.game.iter()));
+            game_args.extend(map_args(j.arguments.game.clone())?);
+            game_args.push(a1);
+
+            let a2 = Argument::without_rules(map_unconditional_args(j.arguments.jvm.iter()));
+            jvm_args.extend(map_args(j.arguments.jvm.clone())?);
+            jvm_args.push(a2);
+        }
 
-        let a2 = Argument::without_rules(map_unconditional_args(jvm_args_internal.iter()));
-        let mut jvm_args: Vec<Argument> = map_args(jvm_args_internal)?;
-        jvm_args.push(a2);
+        // Libraries of children take priority over libraries with the same name from their parents
+        let mut libs: Vec<LibraryInfo> = Vec::new();
+        for j in &chain {
+            let extend_by = j.libraries.iter().cloned().map(map_library).collect::<Result<Vec<Vec<LibraryInfo>>, D::Error>>()?;
+            let extend_by: Vec<LibraryInfo> = extend_by.into_iter().flatten().filter(|lib| libs.iter().filter(|l| l.name == lib.name).next().is_none()).collect();
+            libs.extend(extend_by);
+        }
 
-        let mut libs = json.libraries.into_iter().map(map_library).filter_map(flip_result_option).collect::<Result<Vec<LibraryInfo>, D::Error>>()?;
         // Add Main Jar as a library since its easier that way
-        let downloads = first_or_second_or_missing(json.downloads, inherited.as_ref(), |j| Ok(j.downloads), "downloads")?;
+        let downloads = first_in_chain(&chain, |j| Ok(j.downloads), "downloads")?;
         libs.push(LibraryInfo::new(
             Url::parse(downloads.client.url).map_err(|e| Error::custom(e))?,
             match downloads.client.sha1 {
@@ -155,28 +200,17 @@ impl<'de> Deserialize<'de> for Version {
             String::from("client"),
         ));
 
-        let assets = first_or_second_or_missing(unpack_assets_index(json.asset_index)?, inherited.as_ref(), |j| unpack_assets_index(j.asset_index), "assetIndex")?;
+        let assets = first_in_chain(&chain, |j| unpack_assets_index(j.asset_index), "assetIndex")?;
 
-        let log_info = first_or_second_or_missing(unpack_log_config(json.logging)?, inherited.as_ref(), |j| unpack_log_config(j.logging), "logging")?;
-        let log_arg = first_or_second_or_missing(unpack_option(json.logging, |l| l.client), inherited.as_ref(), |j| Ok(unpack_option(j.logging, |l| l.client)), "logging")?.argument.replace("${path}", "${logging_path}");
-        jvm_args.push(Argument::without_rules(vec![log_arg]));
-
-        if let Some(inherit) = inherited {
-            let a1 = Argument::without_rules(map_unconditional_args(inherit.arguments.game.iter()));
-            game_args.extend(map_args(inherit.arguments.game)?);
-            game_args.push(a1);
-            let a2 = Argument::without_rules(map_unconditional_args(inherit.arguments.jvm.iter()));
-            jvm_args.extend(map_args(inherit.arguments.jvm)?);
-            jvm_args.push(a2);
-            let extend_by = inherit.libraries.into_iter().map(map_library).filter_map(flip_result_option).filter(|l| match l {
-                Ok(lib) => {
-                    libs.iter().filter(|l| l.name == lib.name).next().is_none()
-                }
-                Err(_) => { true }
-            }).collect::<Result<Vec<LibraryInfo>, D::Error>>()?;
-            libs.extend(extend_by);
+        // Really old versions don't have a log config
+        let log_info = chain.iter().map(|j| unpack_log_config::<D::Error>(j.logging)).find_map(flip_result_option).transpose()?;
+        if let Some(log_config) = chain.iter().find_map(|j| unpack_option(j.logging, |l| l.client)) {
+            jvm_args.push(Argument::without_rules(vec![log_config.argument.replace("${path}", "${logging_path}")]));
         }
 
+        // Versions from before the runtime manifests existed all run on java 8
+        let java_version = chain.iter().find_map(|j| j.java_version.as_ref()).map(|j| JavaVersionInfo::new(String::from(j.component), j.major_version)).unwrap_or_default();
+
         // TODO resolve references and consolidate structs
 
         Ok(Self {
@@ -189,19 +223,85 @@ impl<'de> Deserialize<'de> for Version {
             libs,
             assets,
             log_info,
+            java_version,
         })
     }
 }
 
-fn first_or_second_or_missing<T, S, F, E>(first: Option<T>, second_container: Option<S>, unpacker: F, error_msg: &'static str) -> Result<T, E>
+///
+/// Reads the contents of every json that `version_id` (directly or indirectly) inherits from, ordered from its direct parent to the base json.
+/// Parents that are not on disk yet are downloaded if they are vanilla versions.
+///
+fn read_inheritance_chain<E: Error>(version_id: &str, inherits_from: Option<&str>) -> Result<Vec<String>, E> {
+    resolve_inheritance_chain(version_id, inherits_from, |parent_id| fs::read_to_string(get_inherited_json_path::<E>(parent_id)?).map_err(Error::custom))
+}
+
+///
+/// Walks the chain of parents, reading each json with `read_json`. Fails if a version (indirectly) inherits from itself.
+///
+fn resolve_inheritance_chain<E: Error, F: FnMut(&str) -> Result<String, E>>(version_id: &str, inherits_from: Option<&str>, mut read_json: F) -> Result<Vec<String>, E> {
+    let mut visited: HashSet<String> = HashSet::new();
+    visited.insert(version_id.to_owned());
+    let mut files = Vec::new();
+    let mut next = inherits_from.map(String::from);
+
+    while let Some(parent_id) = next {
+        if !visited.insert(parent_id.clone()) {
+            return Err(Error::custom(format!("Client json inheritance cycle detected at version `{}`.", parent_id)));
+        }
+        let file = read_json(parent_id.as_str())?;
+        next = serde_json::from_str::<InheritanceInfo>(file.as_str()).map_err(Error::custom)?.inherits_from;
+        files.push(file);
+    }
+    Ok(files)
+}
+
+///
+/// Finds the json file for an inherited version, downloading it first if it is a vanilla version.
+/// Non-vanilla parents are expected to be at `versions/<id>/<id>.json`, just like with the official launcher.
+///
+fn get_inherited_json_path<E: Error>(version_id: &str) -> Result<PathBuf, E> {
+    if let Some(version_info) = GAME_VERSION_MANIFEST.get_version_by_name(version_id) {
+        version_info.download(version_id).map_err(Error::custom)?;
+        return Ok(version_info.get_file_path(version_id));
+    }
+    let path = get_custom_client_json_path(version_id);
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(Error::custom(format!("Unable to find inherited client json `{}`.", version_id)))
+    }
+}
+
+///
+/// The jvm arguments the official launcher uses for versions that only specify `minecraftArguments`
+///
+fn legacy_jvm_args() -> Vec<Argument> {
+    vec![
+        Argument::with_rules(vec![String::from("-XstartOnFirstThread")], vec![Rule::new(RuleAction::Allow, vec![RuleCondition::Os(Os::Osx)])]),
+        Argument::with_rules(vec![String::from("-XX:HeapDumpPath=MojangTricksIntelDriversForPerformance_javaw.exe_minecraft.exe.heapdump")], vec![Rule::new(RuleAction::Allow, vec![RuleCondition::Os(Os::Windows)])]),
+        Argument::with_rules(vec![String::from("-Xss1M")], vec![Rule::new(RuleAction::Allow, vec![RuleCondition::Arch(Architecture::X86)])]),
+        Argument::without_rules(vec![
+            String::from("-Djava.library.path=${natives_directory}"),
+            String::from("-Dminecraft.launcher.brand=${launcher_name}"),
+            String::from("-Dminecraft.launcher.version=${launcher_version}"),
+            String::from("-cp"),
+            String::from("${classpath}"),
+        ]),
+    ]
+}
+
+fn first_in_chain<'file, T, F, E>(chain: &[ClientJson<'file>], mut unpacker: F, error_msg: &'static str) -> Result<T, E>
 where
     E: de::Error,
-    F: FnOnce(S) -> Result<Option<T>, E>,
+    F: FnMut(&ClientJson<'file>) -> Result<Option<T>, E>,
 {
-    match first {
-        None => unpacker(second_container.ok_or(Error::missing_field(error_msg))?)?.ok_or(Error::missing_field(error_msg)),
-        Some(s) => Ok(s),
+    for json in chain {
+        if let Some(t) = unpacker(json)? {
+            return Ok(t);
+        }
     }
+    Err(Error::missing_field(error_msg))
 }
 
 fn map_args<E: Error>(args_in: Vec<internal::Arg>) -> Result<Vec<Argument>, E> {
@@ -226,10 +326,10 @@ fn map_args<E: Error>(args_in: Vec<internal::Arg>) -> Result<Vec<Argument>, E> {
 }
 
 fn map_unconditional_args<'a>(args_in: impl Iterator<Item = &'a internal::Arg<'a>>) -> Vec<String> {
-    args_in.map(|a| if let internal::Arg::Always(s) = a { Some(String::from(*s).replace(' ', "")) } else { None }).filter_map(|s| s).collect()
+    args_in.map(|a| if let internal::Arg::Always(s) = a { Some(String::from(*s)) } else { None }).filter_map(|s| s).collect()
 }
 
-fn map_library<E: Error>(lib: internal::Library) -> Result<Option<LibraryInfo>, E> {
+fn map_library<E: Error>(lib: internal::Library) -> Result<Vec<LibraryInfo>, E> {
     const INVALID_MAVEN_NAME_TEXT: &'static str = "Valid maven Identifier: <groupId>:<artifactId>:<version>";
 
     let name = lib.name;
@@ -239,46 +339,72 @@ fn map_library<E: Error>(lib: internal::Library) -> Result<Option<LibraryInfo>,
     for internal_rule in lib.rules {
         let parsed = Rule::try_from_internal(internal_rule)?;
         if !parsed.matches(false, false, false, false, false, false) {
-            return Ok(None);
+            return Ok(Vec::new());
         }
     }
 
+    // Platforms without official natives get them from the override table instead
+    if let Some(overridden) = override_library(name, lib.natives.contains_key(Os::current().as_str()))? {
+        return Ok(overridden);
+    }
+
     let (first, version) = name.rsplit_once(':').ok_or_else(|| E::invalid_value(Unexpected::Str(name), &INVALID_MAVEN_NAME_TEXT))?;
     let (group_id, artifact_id) = first.split_once(':').ok_or_else(|| E::invalid_value(Unexpected::Str(name), &INVALID_MAVEN_NAME_TEXT))?;
 
     let group_id_url = group_id.replace('.', "/");
 
-    let url = match lib.format {
-        LibraryFormat::Artifact { ref downloads } => match downloads.artifact.info.url {
-            "" => {
-                Url::parse("about:blank").map_err(E::custom)?
-            },
-            url => {
-                Url::parse(url).map_err(E::custom)?
-            },
-        },
-        LibraryFormat::Plain { ref info } => Url::parse(format!("{}{}/{}/{}/{}-{}.jar", info.url, group_id_url.as_str(), artifact_id, version, artifact_id, version).as_str()).map_err(E::custom)?,
-    };
-    let name = match lib.format {
-        LibraryFormat::Artifact { ref downloads } => String::from(downloads.artifact.path.rsplit_once('/').map(|s| s.1).unwrap_or(downloads.artifact.path)),
-        LibraryFormat::Plain { ref info } => format!("{}-{}.jar", artifact_id, version),
-    };
-    let size = match lib.format {
-        LibraryFormat::Artifact { ref downloads } => downloads.artifact.info.size,
-        LibraryFormat::Plain { ref info } => info.size,
-    };
-    let check = match lib.format {
-        LibraryFormat::Artifact { ref downloads } => match downloads.artifact.info.sha1 {
-            None => None,
-            Some(s) => Some(FileHash::Sha1(hash::sha1_from_base64_str(s)?)),
+    // Older versions ship natives as a separate classifier jar per os, e.g. `natives-windows-${arch}`
+    let natives_classifier = lib.natives.get(Os::current().as_str()).map(|c| c.replace("${arch}", if cfg!(target_pointer_width = "64") { "64" } else { "32" }));
+    let exclude: Vec<String> = lib.extract.map(|e| e.exclude.iter().map(|s| String::from(*s)).collect()).unwrap_or_default();
+
+    let mut mapped = Vec::new();
+    match lib.format {
+        LibraryFormat::Artifact { downloads } => {
+            if let Some(artifact) = downloads.artifact {
+                mapped.push(map_artifact_download(&artifact, first.to_owned())?);
+            }
+            if let Some(classifier) = natives_classifier {
+                let artifact = downloads.classifiers.get(classifier.as_str()).ok_or_else(|| E::custom(format!("Library {} has no download for the classifier {}", name, classifier)))?;
+                mapped.push(map_artifact_download(artifact, format!("{}:{}", first, classifier))?.with_natives(exclude, false));
+            }
         },
-        LibraryFormat::Plain { ref info } => match info.sha1 {
-            None => None,
-            Some(s) => Some(FileHash::Sha1(hash::sha1_from_base64_str(s)?)),
+        LibraryFormat::Plain { info } => match natives_classifier {
+            None => mapped.push(LibraryInfo::new(
+                Url::parse(format!("{}{}/{}/{}/{}-{}.jar", info.url, group_id_url.as_str(), artifact_id, version, artifact_id, version).as_str()).map_err(E::custom)?,
+                match info.sha1 {
+                    None => None,
+                    Some(s) => Some(FileHash::Sha1(hash::sha1_from_base64_str(s)?)),
+                },
+                info.size,
+                format!("{}-{}.jar", artifact_id, version),
+                first.to_owned(),
+            )),
+            // The hash and size belong to the main jar, so the classifier jar can't be verified
+            Some(classifier) => mapped.push(LibraryInfo::new(
+                Url::parse(format!("{}{}/{}/{}/{}-{}-{}.jar", info.url, group_id_url.as_str(), artifact_id, version, artifact_id, version, classifier).as_str()).map_err(E::custom)?,
+                None,
+                None,
+                format!("{}-{}-{}.jar", artifact_id, version, classifier),
+                format!("{}:{}", first, classifier),
+            ).with_natives(exclude, false)),
         },
     };
 
-    Ok(Some(LibraryInfo::new(url, check, size, name, first.to_owned())))
+    // Newer versions ship natives as regular libraries which also need to be on the classpath
+    Ok(mapped.into_iter().map(|l| if l.extract_exclude.is_none() && l.file_name.ends_with(NATIVE_JARS_SUFFIX) { l.with_natives(Vec::new(), true) } else { l }).collect())
+}
+
+fn map_artifact_download<E: Error>(artifact: &internal::ArtifactDownload, name: String) -> Result<LibraryInfo, E> {
+    let url = match artifact.info.url {
+        "" => Url::parse("about:blank").map_err(E::custom)?,
+        url => Url::parse(url).map_err(E::custom)?,
+    };
+    let check = match artifact.info.sha1 {
+        None => None,
+        Some(s) => Some(FileHash::Sha1(hash::sha1_from_base64_str(s)?)),
+    };
+    let file_name = String::from(artifact.path.rsplit_once('/').map(|s| s.1).unwrap_or(artifact.path));
+    Ok(LibraryInfo::new(url, check, artifact.info.size, file_name, name))
 }
 
 fn unpack_assets_index<E: Error>(info: Option<internal::AssetIndexInfo>) -> Result<Option<AssetsIndexInfo>, E> {
@@ -366,38 +492,34 @@ impl Argument {
 #[derive(Debug, Clone)]
 pub struct Rule {
     action: RuleAction,
-    condition: Option<RuleCondition>,
+    // All of them have to match for the action to apply, a rule without conditions always applies
+    conditions: Vec<RuleCondition>,
 }
 
 impl Rule {
     fn try_from_internal<T: Error>(value: internal::Rule) -> Result<Self, T> {
         let action = value.action;
-        let mut condition = None;
-        if value.os.len() + value.features.len() > 1 {
-            return Err(Error::custom("Only one condition per rule is currently supported."));
-        }
+        let mut conditions = Vec::with_capacity(value.os.len() + value.features.len());
         for (k, v) in value.os {
-            condition = Some(match k {
+            conditions.push(match k {
                 "name" => RuleCondition::Os(Os::from_str(v.as_str()).ok_or(Error::invalid_value(Unexpected::Str(v.as_str()), &"`windows`, `osx`, or `linux`"))?),
                 "arch" => RuleCondition::Arch(Architecture::from_str(v.as_str()).ok_or(Error::invalid_value(Unexpected::Str(v.as_str()), &"`x86`, `x64`, or `arm64`"))?),
                 "version" => RuleCondition::OsVersion(Regex::new(v.as_str()).map_err(Error::custom)?),
                 unknown => return Err(Error::unknown_field(unknown, &["name", "arch", "version"])),
             });
-            break;
         }
         for (k, v) in value.features {
-            condition = Some(match k {
+            conditions.push(match k {
                 "is_demo_user" => RuleCondition::IsDemoUser(v),
                 "has_custom_resolution" => RuleCondition::HasCustomResolution(v),
                 "has_quick_plays_support" => RuleCondition::HasQuickPlaySupport(v),
                 "is_quick_play_singleplayer" => RuleCondition::IsQuickPlaySingleplayer(v),
                 "is_quick_play_multiplayer" => RuleCondition::IsQuickPlayMultiplayer(v),
                 "is_quick_play_realms" => RuleCondition::IsQuickPlayRealms(v),
-                unknown => return Err(Error::unknown_field(unknown, &["is_demo_user", "has_custom_resolution", "has_quick_plays_support", "is_quick_play_singleplayer", "is_quick_play_multiplayer", "is_quick_play_realms"])),
+                unknown => RuleCondition::UnknownFeature(unknown.to_owned(), v),
             });
-            break;
         }
-        Ok(Self { action, condition })
+        Ok(Self { action, conditions })
     }
 
     pub fn matches(&self, is_demo_user: bool, has_custom_resolution: bool, has_quick_play_support: bool, has_quick_play_singleplayer: bool, has_quick_play_multiplayer: bool, has_quick_play_realms: bool) -> bool {
@@ -405,13 +527,13 @@ impl Rule {
             RuleAction::Allow => false,
             RuleAction::Disallow => true,
         };
-        modifier ^ self.condition.as_ref().map(|c| c.matches(is_demo_user, has_custom_resolution, has_quick_play_support, has_quick_play_singleplayer, has_quick_play_multiplayer, has_quick_play_realms)).unwrap_or(true)
+        modifier ^ self.conditions.iter().all(|c| c.matches(is_demo_user, has_custom_resolution, has_quick_play_support, has_quick_play_singleplayer, has_quick_play_multiplayer, has_quick_play_realms))
     }
 }
 
 impl Rule {
-    pub fn new(action: RuleAction, condition: Option<RuleCondition>) -> Self {
-        Self { action, condition }
+    pub fn new(action: RuleAction, conditions: Vec<RuleCondition>) -> Self {
+        Self { action, conditions }
     }
 }
 
@@ -423,6 +545,8 @@ pub enum RuleCondition {
     IsQuickPlaySingleplayer(bool),
     IsQuickPlayMultiplayer(bool),
     IsQuickPlayRealms(bool),
+    // Features the launcher doesn't know about are never enabled
+    UnknownFeature(String, bool),
     Arch(Architecture),
     Os(Os),
     OsVersion(Regex),
@@ -437,7 +561,8 @@ impl RuleCondition {
             RuleCondition::IsQuickPlaySingleplayer(b) => *b == has_quick_play_singleplayer,
             RuleCondition::IsQuickPlayMultiplayer(b) => *b == has_quick_play_multiplayer,
             RuleCondition::IsQuickPlayRealms(b) => *b == has_quick_play_realms,
-            RuleCondition::Arch(a) => *a == Architecture::current(),
+            RuleCondition::UnknownFeature(_, b) => !*b,
+            RuleCondition::Arch(a) => Some(*a) == Architecture::current(),
             RuleCondition::Os(os) => *os == Os::current(),
             RuleCondition::OsVersion(regex) => if let Some(ver) = get_os_version() { regex.is_match(ver.as_str()) } else { true },
         }
@@ -480,13 +605,11 @@ impl Architecture {
         })
     }
 
-    fn current() -> Self {
-        match ARCH {
-            "x86" => Self::X86,
-            "x64" => Self::X64,
-            "x86_64" => Self::X64,
-            _ => panic!("Invalid OS!"),
-        }
+    ///
+    /// None on architectures the game has no natives for, which then don't match any `arch` rule
+    ///
+    pub fn current() -> Option<Self> {
+        Self::from_str(ARCH)
     }
 }
 
@@ -509,6 +632,14 @@ impl Os {
         })
     }
 
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Windows => "windows",
+            Self::Osx => "osx",
+            Self::Linux => "linux",
+        }
+    }
+
     fn current() -> Self {
         match OS {
             "windows" => Self::Windows,
@@ -526,11 +657,28 @@ pub struct LibraryInfo {
     size: Option<NonZeroU64>,
     file_name: String,
     name: String,
+    // Path prefixes inside the jar to skip, only present if natives should be extracted from it
+    extract_exclude: Option<Vec<String>>,
+    on_classpath: bool,
 }
 
 impl LibraryInfo {
     pub fn new(download_url: Url, verifier: Option<FileHash>, size: Option<NonZeroU64>, file_name: String, name: String) -> Self {
-        Self { download_url, verifier, size, file_name, name }
+        Self { download_url, verifier, size, file_name, name, extract_exclude: None, on_classpath: true }
+    }
+
+    pub fn with_natives(mut self, extract_exclude: Vec<String>, on_classpath: bool) -> Self {
+        self.extract_exclude = Some(extract_exclude);
+        self.on_classpath = on_classpath;
+        self
+    }
+
+    pub fn extract_exclude(&self) -> Option<&[String]> {
+        self.extract_exclude.as_deref()
+    }
+
+    pub fn on_classpath(&self) -> bool {
+        self.on_classpath
     }
 }
 
@@ -572,6 +720,13 @@ impl AssetsIndexInfo {
     pub fn id(&self) -> &str {
         &self.id
     }
+
+    ///
+    /// The name of the index, as used for `${assets_index_name}` and the virtual assets folder
+    ///
+    pub fn name(&self) -> &str {
+        self.id.strip_suffix(".json").unwrap_or(&self.id)
+    }
 }
 
 impl Downloadable for AssetsIndexInfo {
@@ -592,6 +747,32 @@ impl Downloadable for AssetsIndexInfo {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JavaVersionInfo {
+    component: String,
+    major_version: u8,
+}
+
+impl JavaVersionInfo {
+    pub fn new(component: String, major_version: u8) -> Self {
+        Self { component, major_version }
+    }
+
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+
+    pub fn major_version(&self) -> u8 {
+        self.major_version
+    }
+}
+
+impl Default for JavaVersionInfo {
+    fn default() -> Self {
+        Self::new(String::from("jre-legacy"), 8)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogConfigInfo {
     id: String,
@@ -648,3 +829,57 @@ mod tests {
     }
 
 }*/
+
+#[cfg(test)]
+mod rule_tests {
+    use crate::launcher_rewrite::launch_properties::{internal, Os, Rule};
+
+    fn parse_rule(json: &str) -> Rule {
+        let internal: internal::Rule = serde_json::from_str(json).unwrap();
+        Rule::try_from_internal::<serde_json::Error>(internal).unwrap()
+    }
+
+    #[test]
+    fn all_conditions_have_to_match() {
+        let json = format!(r#"{{"action": "allow", "os": {{"name": "{}"}}, "features": {{"is_demo_user": true}}}}"#, Os::current().as_str());
+        let rule = parse_rule(json.as_str());
+        assert!(rule.matches(true, false, false, false, false, false));
+        assert!(!rule.matches(false, false, false, false, false, false));
+    }
+
+    #[test]
+    fn unknown_features_are_disabled() {
+        let rule = parse_rule(r#"{"action": "allow", "features": {"has_some_future_feature": true}}"#);
+        assert!(!rule.matches(false, false, false, false, false, false));
+        let rule = parse_rule(r#"{"action": "allow", "features": {"has_some_future_feature": false}}"#);
+        assert!(rule.matches(false, false, false, false, false, false));
+    }
+}
+
+#[cfg(test)]
+mod inheritance_tests {
+    use std::collections::HashMap;
+    use serde::de::Error;
+    use crate::launcher_rewrite::launch_properties::resolve_inheritance_chain;
+
+    fn resolve(version_id: &str, inherits_from: &str, jsons: &[(&str, &str)]) -> Result<Vec<String>, serde_json::Error> {
+        let jsons: HashMap<&str, &str> = jsons.iter().copied().collect();
+        resolve_inheritance_chain(version_id, Some(inherits_from), |id| jsons.get(id).map(|json| json.to_string()).ok_or_else(|| serde_json::Error::custom(format!("missing {}", id))))
+    }
+
+    #[test]
+    fn resolves_every_parent_in_order() {
+        let parent = r#"{"id": "parent", "inheritsFrom": "base"}"#;
+        let base = r#"{"id": "base"}"#;
+        let chain = resolve("child", "parent", &[("parent", parent), ("base", base)]).unwrap();
+        assert_eq!(chain, vec![parent, base]);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let a = r#"{"id": "a", "inheritsFrom": "b"}"#;
+        let b = r#"{"id": "b", "inheritsFrom": "a"}"#;
+        let err = resolve("a", "b", &[("a", a), ("b", b)]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+}
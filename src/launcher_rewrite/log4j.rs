@@ -0,0 +1,246 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::launcher_rewrite::game_output::LogLevel;
+
+const EVENT_START: &str = "<log4j:Event";
+const EVENT_END: &str = "</log4j:Event>";
+// Long stack traces still fit, but a broken event can't hold on to the rest of the output
+const MAX_PENDING_EVENT_LINES: usize = 2_000;
+
+///
+/// A single log message written by the game's `XMLLayout` logger
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEvent {
+    timestamp: DateTime<Utc>,
+    level: LogLevel,
+    thread: String,
+    logger: String,
+    message: String,
+    throwable: Option<String>,
+}
+
+impl LogEvent {
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    pub fn thread(&self) -> &str {
+        &self.thread
+    }
+
+    pub fn logger(&self) -> &str {
+        &self.logger
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn throwable(&self) -> Option<&str> {
+        self.throwable.as_deref()
+    }
+
+    ///
+    /// Formats the event the same way the game's console pattern does, e.g. `[12:00:00] [Render thread/INFO]: Setting user: Player`
+    ///
+    pub fn to_plain_text(&self) -> String {
+        let time = self.timestamp.with_timezone(&Local).format("%H:%M:%S");
+        let mut text = format!("[{}] [{}/{}]: {}", time, self.thread, self.level, self.message);
+        if let Some(throwable) = &self.throwable {
+            text.push('\n');
+            text.push_str(throwable.trim_end());
+        }
+        text
+    }
+
+    fn parse(fragment: &str) -> Option<Self> {
+        let mut reader = Reader::from_str(fragment);
+        let mut event: Option<LogEvent> = None;
+        // Local name of the element whose text is currently being read
+        let mut current_element: Vec<u8> = Vec::new();
+
+        loop {
+            match reader.read_event().ok()? {
+                Event::Start(start) if start.local_name().as_ref() == b"Event" => {
+                    event = Some(Self::from_attributes(&start)?);
+                }
+                Event::Empty(start) if start.local_name().as_ref() == b"Event" => {
+                    event = Some(Self::from_attributes(&start)?);
+                }
+                Event::Start(start) => {
+                    current_element = start.local_name().as_ref().to_vec();
+                }
+                Event::End(_) => {
+                    current_element.clear();
+                }
+                Event::CData(data) => {
+                    let content = String::from_utf8_lossy(&data.into_inner()).into_owned();
+                    event.as_mut()?.set_element_text(&current_element, content);
+                }
+                Event::Text(data) => {
+                    let content = data.unescape().ok()?.into_owned();
+                    if !content.trim().is_empty() {
+                        event.as_mut()?.set_element_text(&current_element, content);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        event
+    }
+
+    fn from_attributes(start: &BytesStart) -> Option<Self> {
+        let attribute = |name: &str| -> Option<String> {
+            start.try_get_attribute(name).ok()?.and_then(|a| a.unescape_value().ok()).map(|v| v.into_owned())
+        };
+        let timestamp = attribute("timestamp")?.parse::<i64>().ok().and_then(|millis| Utc.timestamp_millis_opt(millis).single())?;
+        Some(Self {
+            timestamp,
+            level: LogLevel::from_str(attribute("level")?.as_str())?,
+            thread: attribute("thread").unwrap_or_default(),
+            logger: attribute("logger").unwrap_or_default(),
+            message: String::new(),
+            throwable: None,
+        })
+    }
+
+    fn set_element_text(&mut self, element: &[u8], content: String) {
+        match element {
+            b"Message" => self.message.push_str(&content),
+            b"Throwable" => self.throwable.get_or_insert_with(String::new).push_str(&content),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogRecord {
+    Event(LogEvent),
+    // Anything written to the stream that isn't a log4j event, like `System.out.println` calls from mods
+    Text(String),
+}
+
+///
+/// Turns the game's stdout into log records one line at a time, since events span several lines
+///
+#[derive(Debug, Default)]
+pub struct Log4jStreamParser {
+    pending_event: Option<String>,
+    pending_lines: usize,
+}
+
+impl Log4jStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns the records completed by this line, which can be two if it starts a new event
+    /// before the previous one ended
+    ///
+    pub fn push_line(&mut self, line: &str) -> Vec<LogRecord> {
+        let mut records = Vec::new();
+        let starts_event = line.trim_start().starts_with(EVENT_START);
+        // An event that never ended (or isn't an event at all, like a mod printing XML) would
+        // otherwise swallow every line after it, so show what we have as is
+        if self.pending_event.is_some() && (starts_event || self.pending_lines >= MAX_PENDING_EVENT_LINES) {
+            records.extend(self.finish());
+        }
+
+        match self.pending_event.as_mut() {
+            Some(pending) => {
+                pending.push('\n');
+                pending.push_str(line);
+                self.pending_lines += 1;
+            }
+            None if starts_event => {
+                self.pending_event = Some(line.to_owned());
+                self.pending_lines = 1;
+            }
+            None => {
+                records.push(LogRecord::Text(line.to_owned()));
+                return records;
+            }
+        }
+
+        if line.contains(EVENT_END) {
+            if let Some(fragment) = self.pending_event.take() {
+                // If an event can't be parsed, show it as is rather than dropping it
+                records.push(LogEvent::parse(&fragment).map(LogRecord::Event).unwrap_or(LogRecord::Text(fragment)));
+            }
+        }
+        records
+    }
+
+    ///
+    /// Returns whatever is left of an unfinished event once the stream has ended
+    ///
+    pub fn finish(&mut self) -> Option<LogRecord> {
+        self.pending_lines = 0;
+        self.pending_event.take().map(LogRecord::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::launcher_rewrite::game_output::LogLevel;
+    use crate::launcher_rewrite::log4j::{Log4jStreamParser, LogRecord, MAX_PENDING_EVENT_LINES};
+
+    #[test]
+    fn parses_xml_layout_events() {
+        let output = r#"Plain line
+<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="ERROR" thread="Render thread">
+  <log4j:Message><![CDATA[Failed to load <something> & more]]></log4j:Message>
+  <log4j:Throwable><![CDATA[java.lang.RuntimeException: boom
+	at Main.main(Main.java:1)
+]]></log4j:Throwable>
+</log4j:Event>"#;
+
+        let mut parser = Log4jStreamParser::new();
+        let records: Vec<LogRecord> = output.lines().flat_map(|l| parser.push_line(l)).collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], LogRecord::Text("Plain line".to_owned()));
+        let LogRecord::Event(event) = &records[1] else { panic!("Expected a log event, got {:?}", records[1]) };
+        assert_eq!(event.level(), LogLevel::Error);
+        assert_eq!(event.thread(), "Render thread");
+        assert_eq!(event.logger(), "net.minecraft.client.Minecraft");
+        assert_eq!(event.message(), "Failed to load <something> & more");
+        assert_eq!(event.timestamp().timestamp_millis(), 1700000000000);
+        assert!(event.throwable().unwrap().starts_with("java.lang.RuntimeException: boom"));
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn flushes_unterminated_events() {
+        let output = r#"<log4j:Event logger="Mod" timestamp="1700000000000" level="INFO" thread="main">
+Printed by a mod
+<log4j:Event logger="Mod" timestamp="1700000000000" level="WARN" thread="main"><log4j:Message><![CDATA[Next]]></log4j:Message></log4j:Event>
+After"#;
+
+        let mut parser = Log4jStreamParser::new();
+        let records: Vec<LogRecord> = output.lines().flat_map(|l| parser.push_line(l)).collect();
+
+        assert_eq!(records.len(), 3);
+        let LogRecord::Text(text) = &records[0] else { panic!("Expected text, got {:?}", records[0]) };
+        assert!(text.ends_with("Printed by a mod"));
+        let LogRecord::Event(event) = &records[1] else { panic!("Expected a log event, got {:?}", records[1]) };
+        assert_eq!(event.level(), LogLevel::Warn);
+        assert_eq!(event.message(), "Next");
+        assert_eq!(records[2], LogRecord::Text("After".to_owned()));
+
+        parser.push_line("<log4j:Event level=\"INFO\">");
+        let records: Vec<LogRecord> = (0..MAX_PENDING_EVENT_LINES).flat_map(|i| parser.push_line(&i.to_string())).collect();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(&records[0], LogRecord::Text(text) if text.ends_with(&(MAX_PENDING_EVENT_LINES - 2).to_string())));
+        assert_eq!(records[1], LogRecord::Text((MAX_PENDING_EVENT_LINES - 1).to_string()));
+        assert!(parser.finish().is_none());
+    }
+}
@@ -1,26 +1,37 @@
-use crate::launcher_rewrite::authentication::LOGGED_IN_ACCOUNT_DATA;
+use crate::launcher_rewrite::authentication::{ensure_active_account_fresh, LOGGED_IN_ACCOUNT_DATA};
 use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::hooks::LaunchHooks;
+use crate::launcher_rewrite::instances::RunningInstance;
 use crate::launcher_rewrite::installed_versions::INSTALLED_VERSIONS;
 use crate::launcher_rewrite::installer::Downloadable;
+use crate::launcher_rewrite::java::{check_java_compatibility, ensure_runtime_installed};
+use crate::launcher_rewrite::launch_cmd::LaunchCommand;
 use crate::launcher_rewrite::launch_properties::Version;
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::path_handler::get_vanilla_client_json_path;
-use crate::launcher_rewrite::profiles::{convert_width_height, ModLoader, PROFILES};
+use crate::launcher_rewrite::profiles::{convert_width_height, ModLoader, QuickPlay, PROFILES};
+use crate::launcher_rewrite::util::shell_args::split_shell_args;
 use std::fs;
-use std::path::Path;
-use std::sync::atomic::AtomicUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub mod assets;
 pub mod authentication;
+pub mod crash;
 pub mod error;
 mod fabric;
 mod forge;
+pub mod game_output;
+pub mod hooks;
 mod game_version;
 pub mod installed_versions;
 pub mod installer;
+pub mod instances;
+pub mod java;
 mod jar_utils;
 pub mod launch_cmd;
 pub mod launch_properties;
+pub mod log4j;
 pub mod manifest;
 pub mod mod_loader_version_manifest;
 mod neo_forge;
@@ -32,28 +43,79 @@ pub mod urls;
 pub mod util;
 pub mod version_type;
 
-// Number of game instances open. Know this so that way we can refrain from exiting the launcher process until all game instances were closed by the user.
-pub static GAME_INSTANCE_COUNT: AtomicUsize = AtomicUsize::new(0);
-
-pub fn launch_game_from_profile(profile_id: u128) -> Result<(), LauncherError> {
+pub fn launch_game_from_profile(profile_id: u128) -> Result<Arc<RunningInstance>, LauncherError> {
     let profile_lock = PROFILES.read().unwrap();
     let profile = profile_lock.find_profile(profile_id);
     if let Some(profile) = profile {
-        launch_game(profile.version_name(), profile.mod_loader(), profile.mod_loader_version(), profile.width(), profile.height(), Path::new(profile.mc_directory()), profile.memory())
+        launch_game(Some(profile_id), profile.version_name(), profile.mod_loader(), profile.mod_loader_version(), profile.width(), profile.height(), &profile.game_dir(), profile.memory(), profile.java_executable().map(Path::new), profile.additional_args().as_deref(), profile.quick_play(), &LaunchHooks::from_profile(profile))
     } else {
         eprintln!("Attempted to launch nonexistent profile with id {}!", profile_id);
         Err(LauncherError::ProfileError)
     }
 }
 
-pub fn launch_game(game_version: &str, mod_loader: ModLoader, loader_version: &str, width: Option<u32>, height: Option<u32>, dir: &Path, memory: u16) -> Result<(), LauncherError> {
+pub fn launch_game(profile_id: Option<u128>, game_version: &str, mod_loader: ModLoader, loader_version: &str, width: Option<u32>, height: Option<u32>, dir: &Path, memory: u16, java_executable: Option<&Path>, additional_args: Option<&str>, quick_play: Option<&QuickPlay>, hooks: &LaunchHooks) -> Result<Arc<RunningInstance>, LauncherError> {
+    // Checked first, so that way a missing or expired account doesn't only show up after a full install
+    ensure_active_account_fresh()?;
+    let prepared = prepare_game(game_version, mod_loader, loader_version, java_executable, additional_args)?;
+
+    let acc_lock = LOGGED_IN_ACCOUNT_DATA.read().unwrap();
+    let current_account = acc_lock.active_account().ok_or(LauncherError::NoActiveAccount)?;
+    let res = convert_width_height(width, height);
+
+    prepared.version.launch(profile_id, &prepared.vanilla_id, mod_loader, prepared.loader_version.as_deref(), hooks, &prepared.java, current_account, res, memory, &prepared.additional_args, quick_play, dir)
+}
+
+pub fn get_launch_command_from_profile(profile_id: u128) -> Result<LaunchCommand, LauncherError> {
+    let profile_lock = PROFILES.read().unwrap();
+    let profile = profile_lock.find_profile(profile_id).ok_or(LauncherError::ProfileError)?;
+    get_launch_command(profile.version_name(), profile.mod_loader(), profile.mod_loader_version(), profile.width(), profile.height(), &profile.game_dir(), profile.memory(), profile.java_executable().map(Path::new), profile.additional_args().as_deref(), profile.quick_play(), &LaunchHooks::from_profile(profile))
+}
+
+///
+/// Does everything `launch_game` does, including installing the version, but returns the command instead of running it
+///
+pub fn get_launch_command(game_version: &str, mod_loader: ModLoader, loader_version: &str, width: Option<u32>, height: Option<u32>, dir: &Path, memory: u16, java_executable: Option<&Path>, additional_args: Option<&str>, quick_play: Option<&QuickPlay>, hooks: &LaunchHooks) -> Result<LaunchCommand, LauncherError> {
+    // Checked first, so that way a missing or expired account doesn't only show up after a full install
+    ensure_active_account_fresh()?;
+    let prepared = prepare_game(game_version, mod_loader, loader_version, java_executable, additional_args)?;
+
+    let acc_lock = LOGGED_IN_ACCOUNT_DATA.read().unwrap();
+    let current_account = acc_lock.active_account().ok_or(LauncherError::NoActiveAccount)?;
+    let res = convert_width_height(width, height);
+
+    prepared.version.launch_command(&prepared.vanilla_id, mod_loader, prepared.loader_version.as_deref(), hooks, &prepared.java, current_account, res, memory, &prepared.additional_args, quick_play, dir)
+}
+
+///
+/// The java Mojang recommends for a vanilla version, whose json has to be downloaded already. The runtime itself is installed if needed.
+///
+pub(crate) fn recommended_java(vanilla_id: &str) -> Result<PathBuf, LauncherError> {
+    let vanilla_json = fs::read_to_string(get_vanilla_client_json_path(vanilla_id, ModLoader::Vanilla, ""))?;
+    let vanilla: Version = serde_json::from_str(vanilla_json.as_str())?;
+    ensure_runtime_installed(vanilla.java_version())
+}
+
+// A version that is installed and ready to launch
+struct PreparedGame {
+    version: Version,
+    // The minecraft version, which the id of a modded version doesn't match
+    vanilla_id: String,
+    loader_version: Option<String>,
+    java: PathBuf,
+    additional_args: Vec<String>,
+}
+
+fn prepare_game(game_version: &str, mod_loader: ModLoader, loader_version: &str, java_executable: Option<&Path>, additional_args: Option<&str>) -> Result<PreparedGame, LauncherError> {
+    let additional_args = additional_args.map(split_shell_args).transpose().map_err(|reason| LauncherError::InvalidJvmArguments { reason })?.unwrap_or_default();
+
     let version_info = GAME_VERSION_MANIFEST.get_version_from_str(game_version).ok_or_else(|| {
         eprintln!("Attempted to launch profile with illegal version name {}!", game_version);
         LauncherError::ProfileError
     })?;
 
     let game_version = GAME_VERSION_MANIFEST.sanitize_version_name(game_version, mod_loader);
-    let loader_version_c = mod_loader.get_manifest().map(|m| m.sanitize_loader_version_name(game_version, loader_version));
+    let loader_version_c = mod_loader.get_manifest().map(|m| m.sanitize_loader_version_name(game_version, loader_version)).transpose()?;
     let loader_version = loader_version_c.as_ref().map(|c| c.as_ref());
 
     let need_to_install = !INSTALLED_VERSIONS.read().unwrap().contains(version_info.id(), mod_loader, loader_version);
@@ -63,7 +125,12 @@ pub fn launch_game(game_version: &str, mod_loader: ModLoader, loader_version: &s
         version_info.download(version_info.id())?;
         // Download modded version json if needed
         if let Some(manifest) = mod_loader.get_manifest() {
-            manifest.get_loader_version_info(game_version, loader_version.unwrap()).download(game_version)?;
+            // Installers that need to be run use the same java as the game itself
+            let installer_java = match java_executable {
+                Some(java) => java.to_owned(),
+                None => recommended_java(version_info.id())?,
+            };
+            manifest.get_loader_version_info(game_version, loader_version.unwrap())?.install(game_version, &installer_java)?;
         }
     }
 
@@ -71,6 +138,11 @@ pub fn launch_game(game_version: &str, mod_loader: ModLoader, loader_version: &s
     let client_file_contents = fs::read_to_string(json_path)?;
     let version: Version = serde_json::from_str(client_file_contents.as_str())?;
 
+    // Don't bother installing a version that the chosen java can't run anyway
+    if let Some(java) = java_executable {
+        check_java_compatibility(java, version.java_version())?;
+    }
+
     if need_to_install {
         version.install()?;
     }
@@ -79,13 +151,10 @@ pub fn launch_game(game_version: &str, mod_loader: ModLoader, loader_version: &s
         INSTALLED_VERSIONS.write().unwrap().add(version_info.id(), mod_loader, loader_version);
     }
 
-    let acc_lock = LOGGED_IN_ACCOUNT_DATA.read().unwrap();
-    let current_account = acc_lock.active_account().unwrap();
-    let username = current_account.minecraft_account_info().name();
-    let uuid = current_account.minecraft_account_info().id();
-    let token = current_account.minecraft_token();
-    let res = convert_width_height(width, height);
+    let java = match java_executable {
+        Some(java) => java.to_owned(),
+        None => ensure_runtime_installed(version.java_version())?,
+    };
 
-    version.launch(username, uuid, token, res, memory, dir);
-    Ok(())
+    Ok(PreparedGame { version, vanilla_id: version_info.id().to_owned(), loader_version: loader_version.map(str::to_owned), java, additional_args })
 }
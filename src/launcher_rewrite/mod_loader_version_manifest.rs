@@ -1,12 +1,13 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::num::NonZeroU64;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Mutex, OnceLock};
 use iced::widget::markdown::Url;
-use crate::launcher_rewrite::{fabric, forge, neo_forge, quilt};
-use crate::launcher_rewrite::error::LauncherError;
+use crate::launcher_rewrite::{fabric, forge, neo_forge, quilt, recommended_java};
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
 use crate::launcher_rewrite::installer::Downloadable;
+use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::path_handler::get_vanilla_client_json_path;
 use crate::launcher_rewrite::profiles::ModLoader;
 use crate::launcher_rewrite::util::hash::FileHash;
@@ -27,7 +28,7 @@ pub struct ModLoaderVersionManifest {
 
 impl ModLoaderVersionManifest {
 
-    pub fn get_loader_versions(&self, game_version_name: &str) -> Arc<[ModLoaderVersionInfo]> {
+    pub fn get_loader_versions(&self, game_version_name: &str) -> LauncherResult<Arc<[ModLoaderVersionInfo]>> {
         self.versions_map.get(game_version_name)
     }
 
@@ -36,11 +37,11 @@ impl ModLoaderVersionManifest {
     }
 
     pub fn has_loader_for_game_version(&self, game_version_name: &str) -> bool {
-        !self.get_loader_versions(game_version_name).is_empty()
+        self.get_loader_versions(game_version_name).map(|versions| !versions.is_empty()).unwrap_or(false)
     }
 
     pub fn has_stable_loader_version_for_game_version(&self, game_version_name: &str) -> bool {
-        self.get_loader_versions(game_version_name).iter().filter(|loader_version| loader_version.is_stable()).next().is_some()
+        self.get_loader_versions(game_version_name).map(|versions| versions.iter().any(|loader_version| loader_version.is_stable())).unwrap_or(false)
     }
 
     pub fn contains(&self, game_version_name: &str, loader_version_name: &str) -> bool {
@@ -49,50 +50,54 @@ impl ModLoaderVersionManifest {
                 self.has_stable_loader_version_for_game_version(game_version_name)
             },
             LATEST_BETA_TEXT => {
-                self.get_loader_versions(game_version_name).is_empty()
+                self.get_loader_versions(game_version_name).map(|versions| !versions.is_empty()).unwrap_or(false)
             }
             loader_version_name => {
-                self.get_loader_versions(game_version_name).iter().find(|e| e.version_name == loader_version_name).is_some()
+                self.get_loader_versions(game_version_name).map(|versions| versions.iter().any(|e| e.version_name == loader_version_name)).unwrap_or(false)
             }
         }
     }
 
-    pub fn sanitize_loader_version_name<'a>(&'a self, game_version_name: &str, loader_version_name: &'a str) -> Cow<'a, str> {
+    ///
+    /// Converts `latest-stable` and `latest-beta` into the actual loader version name for `game_version_name`.
+    /// Returns an error if the loader has no (stable) build for that game version.
+    ///
+    pub fn sanitize_loader_version_name<'a>(&'a self, game_version_name: &str, loader_version_name: &'a str) -> LauncherResult<Cow<'a, str>> {
         match loader_version_name {
             LATEST_STABLE_TEXT => {
-                if let Some(v) = self.get_loader_versions(game_version_name).iter().find(|v| v.is_stable()) {
-                    v.version_name.clone().into()
+                if let Some(v) = self.get_loader_versions(game_version_name)?.iter().find(|v| v.is_stable()) {
+                    Ok(v.version_name.clone().into())
                 }
                 else {
-                    panic!("No stable loader version found for loader `{:?}` for game version `{}`.", self.loader, game_version_name);
+                    Err(LauncherError::LoaderVersionNotFound { loader: self.loader, game_version: game_version_name.to_owned(), loader_version: loader_version_name.to_owned() })
                 }
             },
             LATEST_BETA_TEXT => {
-                if let Some(v) = self.get_loader_versions(game_version_name).get(0) {
-                    v.version_name.clone().into()
+                if let Some(v) = self.get_loader_versions(game_version_name)?.first() {
+                    Ok(v.version_name.clone().into())
                 }
                 else {
-                    panic!("No loader version found for loader `{:?}` for game version `{}`.", self.loader, game_version_name);
+                    Err(LauncherError::NoLoaderForGameVersion { loader: self.loader, game_version: game_version_name.to_owned() })
                 }
             }
             n => {
-                n.into()
+                Ok(n.into())
             }
         }
     }
 
     // TODO find a way to do this without cloning?
-    pub fn get_loader_version_info(&self, game_version: &str, loader_version: &str) -> ModLoaderVersionInfo {
-        let loader_version = self.sanitize_loader_version_name(game_version, loader_version);
-        if let Some(ver) = self.get_loader_versions(game_version).iter().find(|v| v.version_name == loader_version) {
-            ver.clone()
+    pub fn get_loader_version_info(&self, game_version: &str, loader_version: &str) -> LauncherResult<ModLoaderVersionInfo> {
+        let loader_version = self.sanitize_loader_version_name(game_version, loader_version)?;
+        if let Some(ver) = self.get_loader_versions(game_version)?.iter().find(|v| v.version_name == loader_version) {
+            Ok(ver.clone())
         }
         else {
-            panic!("Unable to find loader version `{}` for mod loader `{:?}` and game version `{}`", loader_version, self.loader, game_version)
+            Err(LauncherError::LoaderVersionNotFound { loader: self.loader, game_version: game_version.to_owned(), loader_version: loader_version.into_owned() })
         }
     }
 
-    pub fn new(loader: ModLoader, version_func: fn(&str) -> Vec<ModLoaderVersionInfo>, latest_version_func: fn() -> ModLoaderLatestVersionData) -> Self {
+    pub fn new(loader: ModLoader, version_func: fn(&str) -> LauncherResult<Vec<ModLoaderVersionInfo>>, latest_version_func: fn() -> ModLoaderLatestVersionData) -> Self {
         Self { loader, versions_map: ModLoaderVersionMap::new(version_func, Mutex::new(HashMap::new()), latest_version_func) }
     }
 }
@@ -138,6 +143,16 @@ impl ModLoaderVersionInfo {
     pub fn new(version_name: String, version_type: ModLoaderVersionType, version_client_url: Url, loader: ModLoader) -> Self {
         Self { version_name, version_type, version_client_url, loader }
     }
+
+    ///
+    /// Same as [`Downloadable::download`], but runs installers that need java with the given java executable
+    ///
+    pub fn install(&self, game_version: &str, java: &Path) -> LauncherResult<()> {
+        match self.loader {
+            ModLoader::Forge => forge::installer::download(&self, game_version, java),
+            _ => self.download(game_version),
+        }
+    }
 }
 
 impl Downloadable for ModLoaderVersionInfo {
@@ -173,7 +188,11 @@ impl Downloadable for ModLoaderVersionInfo {
             ModLoader::Fabric => unreachable!(),
             ModLoader::Quilt => unreachable!(),
             ModLoader::Forge => {
-                forge::installer::download(&self, game_version)
+                // No java was chosen, so use the one the game version requires
+                let version_info = GAME_VERSION_MANIFEST.get_version_from_str(game_version).ok_or(LauncherError::ProfileError)?;
+                version_info.download(version_info.id())?;
+                let java = recommended_java(version_info.id())?;
+                forge::installer::download(&self, game_version, &java)
             }
             ModLoader::NeoForge => {
                 neo_forge::installer::download(&self, game_version)
@@ -220,22 +239,23 @@ impl ModLoaderLatestVersionData {
 
 #[derive(Debug)]
 pub struct ModLoaderVersionMap {
-    version_getter: fn(&str) -> Vec<ModLoaderVersionInfo>,
+    version_getter: fn(&str) -> LauncherResult<Vec<ModLoaderVersionInfo>>,
     latest_supported_game_version: LazyLock<ModLoaderLatestVersionData>,
     versions_map: Mutex<HashMap<String, Arc<[ModLoaderVersionInfo]>>>,
 }
 
 impl ModLoaderVersionMap {
 
-    pub fn get(&self, game_version: &str) -> Arc<[ModLoaderVersionInfo]> {
+    pub fn get(&self, game_version: &str) -> LauncherResult<Arc<[ModLoaderVersionInfo]>> {
         let mut versions_lock = self.versions_map.lock().unwrap();
         if let Some(val) = versions_lock.get(game_version) {
-            val.clone()
+            Ok(val.clone())
         }
         else {
-            let compatible_versions = (self.version_getter)(game_version);
-            versions_lock.insert(game_version.to_owned(), compatible_versions.into());
-            versions_lock.get(game_version).unwrap().clone()
+            // Errors are not cached so that way a failed request can be retried later
+            let compatible_versions: Arc<[ModLoaderVersionInfo]> = (self.version_getter)(game_version)?.into();
+            versions_lock.insert(game_version.to_owned(), compatible_versions.clone());
+            Ok(compatible_versions)
         }
 
     }
@@ -244,20 +264,11 @@ impl ModLoaderVersionMap {
         &self.latest_supported_game_version
     }
 
-    pub fn contains(&self, game_version: &str) -> bool {
-        let mut versions_lock = self.versions_map.lock().unwrap();
-        let value = versions_lock.get(game_version);
-        if let Some(val) = value {
-            val.is_empty()
-        }
-        else {
-            let compatible_versions = (self.version_getter)(game_version);
-            versions_lock.insert(game_version.to_owned(), compatible_versions.into());
-            versions_lock.get(game_version).unwrap().is_empty()
-        }
+    pub fn contains(&self, game_version: &str) -> LauncherResult<bool> {
+        Ok(!self.get(game_version)?.is_empty())
     }
 
-    pub fn new(version_getter: fn(&str) -> Vec<ModLoaderVersionInfo>, versions_map: Mutex<HashMap<String, Arc<[ModLoaderVersionInfo]>>>, latest_supported_game_version_getter: fn() -> ModLoaderLatestVersionData) -> Self {
+    pub fn new(version_getter: fn(&str) -> LauncherResult<Vec<ModLoaderVersionInfo>>, versions_map: Mutex<HashMap<String, Arc<[ModLoaderVersionInfo]>>>, latest_supported_game_version_getter: fn() -> ModLoaderLatestVersionData) -> Self {
         Self { version_getter, latest_supported_game_version: LazyLock::new(latest_supported_game_version_getter), versions_map }
     }
 }
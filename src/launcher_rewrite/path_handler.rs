@@ -27,9 +27,12 @@ pub const ASSETS_FOLDER: &'static str = "assets";
 pub const INDEXES_FOLDER: &'static str = concatcp!(ASSETS_FOLDER, PATH_SEP, "indexes");
 pub const LOG_CONFIGS_FOLDER: &'static str = concatcp!(ASSETS_FOLDER, PATH_SEP, "log_configs");
 pub const OBJECTS_FOLDER: &'static str = concatcp!(ASSETS_FOLDER, PATH_SEP, "objects");
+pub const VIRTUAL_ASSETS_FOLDER: &'static str = concatcp!(ASSETS_FOLDER, PATH_SEP, "virtual");
 pub const SKINS_FOLDER: &'static str = concatcp!(ASSETS_FOLDER, PATH_SEP, "skins");
 
 pub const BIN_PATH: &'static str = "bin";
+pub const RUNTIMES_FOLDER: &'static str = "runtimes";
+pub const INSTANCES_FOLDER: &'static str = "instances";
 
 pub const VANILLA_MANIFEST_LOCATION: &str = concatcp!(VERSIONS_FOLDER, PATH_SEP, "vanilla_mainifest_v2.json");
 pub const FABRIC_MANIFEST_LOCATION: &str = concatcp!(VERSIONS_FOLDER, PATH_SEP, "fabric_manifest_v2.json");
@@ -38,6 +41,8 @@ pub const QUILT_MANIFEST_LOCATION: &str = concatcp!(VERSIONS_FOLDER, PATH_SEP, "
 pub const LAUNCHER_CFG_LOCATION: &str = "profiles.json";
 pub const TOKENS_FILE_LOCATION: &str = "tokens.json";
 pub const INSTALLED_VERSIONS_FILE_LOCATION: &str = "installed.json";
+pub const PLAY_HISTORY_FILE_LOCATION: &str = "play_history.json";
+pub const CLIENT_ID_FILE_LOCATION: &str = "client_id.json";
 
 // Vanilla is special and doesn't need a folder
 pub const VANILLA_CLIENT_JSON_NAME: &str = "vanilla.json";
@@ -51,6 +56,8 @@ pub static GAME_VERSION_MANIFEST_PATH: LazyLock<PathBuf> = LazyLock::new(game_ve
 pub static LAUNCHER_CFG_PATH: LazyLock<PathBuf> = LazyLock::new(launcher_cfg_path);
 pub static TOKENS_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(token_file_path);
 pub static INSTALLED_VERSIONS_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(installed_versions_file_path);
+pub static PLAY_HISTORY_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(play_history_file_path);
+pub static CLIENT_ID_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(client_id_file_path);
 
 fn client_json_name(mod_loader: ModLoader, loader_version: &str) -> Cow<'static, str> {
     match mod_loader {
@@ -89,11 +96,21 @@ pub fn get_vanilla_client_json_path(game_version: &str, mod_loader: ModLoader, l
     let mut cow_loader_version: Cow<str> = loader_version.into();
     let possible_manifest = mod_loader.get_manifest();
     if let Some(manifest) = possible_manifest {
-        cow_loader_version = manifest.sanitize_loader_version_name(game_version, loader_version)
+        // Callers should normally pass an already resolved loader version, so fall back to the raw name if it can't be resolved
+        if let Ok(sanitized) = manifest.sanitize_loader_version_name(game_version, loader_version) {
+            cow_loader_version = sanitized;
+        }
     }
     from_launcher_dir([VERSIONS_FOLDER, game_version, client_json_name(mod_loader, cow_loader_version.as_ref()).as_ref()])
 }
 
+///
+/// Path of a client json that isn't managed by the launcher, following the official launcher's layout of `versions/<id>/<id>.json`
+///
+pub fn get_custom_client_json_path(version_id: &str) -> PathBuf {
+    from_launcher_dir([VERSIONS_FOLDER, version_id, format!("{}.json", version_id).as_str()])
+}
+
 pub fn get_assets_index_dir(index_name: &str) -> PathBuf {
     from_launcher_dir([INDEXES_FOLDER, index_name])
 }
@@ -106,10 +123,26 @@ pub fn get_objects_dir() -> PathBuf {
     from_launcher_dir([OBJECTS_FOLDER])
 }
 
+pub fn get_virtual_assets_dir(index_name: &str) -> PathBuf {
+    from_launcher_dir([VIRTUAL_ASSETS_FOLDER, index_name])
+}
+
 pub fn get_bin_path(version_name: &str) -> PathBuf {
     from_launcher_dir([VERSIONS_FOLDER, version_name, BIN_PATH])
 }
 
+pub fn get_runtime_dir(component: &str) -> PathBuf {
+    from_launcher_dir([RUNTIMES_FOLDER, component])
+}
+
+pub fn get_instance_dir(dir_name: &str) -> PathBuf {
+    from_launcher_dir([INSTANCES_FOLDER, dir_name])
+}
+
+pub fn get_runtime_manifest_path(component: &str) -> PathBuf {
+    from_launcher_dir([RUNTIMES_FOLDER, format!("{}.json", component).as_str()])
+}
+
 fn game_version_manifest_path() -> PathBuf {
     from_launcher_dir([VANILLA_MANIFEST_LOCATION])
 }
@@ -126,3 +159,11 @@ fn installed_versions_file_path() -> PathBuf {
     from_launcher_dir([INSTALLED_VERSIONS_FILE_LOCATION])
 }
 
+fn play_history_file_path() -> PathBuf {
+    from_launcher_dir([PLAY_HISTORY_FILE_LOCATION])
+}
+
+fn client_id_file_path() -> PathBuf {
+    from_launcher_dir([CLIENT_ID_FILE_LOCATION])
+}
+
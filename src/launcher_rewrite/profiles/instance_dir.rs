@@ -0,0 +1,142 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::launcher_rewrite::error::{LauncherError, LauncherResult};
+use crate::launcher_rewrite::instances::RUNNING_INSTANCES;
+use crate::launcher_rewrite::path_handler::get_instance_dir;
+use crate::launcher_rewrite::profiles::LauncherProfile;
+use crate::launcher_rewrite::util::paths::expand_path;
+
+// Folders of an isolated instance that can be linked to the profile's minecraft directory instead
+pub const SHARED_INSTANCE_FOLDERS: &[&str] = &["screenshots", "resourcepacks", "shaderpacks"];
+
+///
+/// Folder name of the instance of a profile with this name, leaving out characters that aren't allowed in file names
+///
+fn instance_dir_base_name(profile_name: &str) -> String {
+    let name: String = profile_name.trim().chars().map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') { c } else { '_' }).collect();
+    let name = name.trim_matches(['.', ' ']);
+    if name.is_empty() { String::from("instance") } else { name.to_owned() }
+}
+
+///
+/// Whether the folder was named after the profile, possibly with a number added to make it unique
+///
+fn is_named_after(dir_name: &str, profile_name: &str) -> bool {
+    let base = instance_dir_base_name(profile_name);
+    dir_name == base || dir_name.strip_prefix(base.as_str()).and_then(|rest| rest.strip_prefix('-')).is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn unused_instance_dir_name(profile_name: &str) -> String {
+    first_unused_name(profile_name, |name| get_instance_dir(name).exists())
+}
+
+fn first_unused_name<F: Fn(&str) -> bool>(profile_name: &str, exists: F) -> String {
+    let base = instance_dir_base_name(profile_name);
+    (1..).map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) }).find(|name| !exists(name)).unwrap()
+}
+
+///
+/// Creates, renames or links the instance folder so that way it matches the profile. Called whenever a profile is saved.
+///
+pub fn sync_instance_dir(profile: &mut LauncherProfile) -> LauncherResult<()> {
+    if !profile.isolated_instance() {
+        // The folder is kept, so turning isolation back on doesn't lose anything
+        return Ok(());
+    }
+    let dir_name = match profile.instance_dir() {
+        None => unused_instance_dir_name(profile.name()),
+        Some(current) if is_named_after(current, profile.name()) => current.to_owned(),
+        Some(current) => {
+            let current_path = get_instance_dir(current);
+            if !RUNNING_INSTANCES.find_by_profile(profile.id()).is_empty() {
+                return Err(LauncherError::InstanceDirInUse(current_path));
+            }
+            let renamed = unused_instance_dir_name(profile.name());
+            if current_path.exists() {
+                fs::rename(current_path, get_instance_dir(&renamed))?;
+            }
+            renamed
+        }
+    };
+    let path = get_instance_dir(&dir_name);
+    fs::create_dir_all(&path)?;
+    if profile.link_shared_folders() {
+        link_shared_folders(&path, &expand_path(profile.mc_directory()))?;
+    }
+    profile.set_instance_dir(Some(dir_name));
+    Ok(())
+}
+
+///
+/// Deletes the instance folder of the profile with everything in it
+///
+pub fn delete_instance_dir(profile: &LauncherProfile) -> LauncherResult<()> {
+    let Some(dir_name) = profile.instance_dir() else { return Ok(()) };
+    let path = get_instance_dir(dir_name);
+    if !RUNNING_INSTANCES.find_by_profile(profile.id()).is_empty() {
+        return Err(LauncherError::InstanceDirInUse(path));
+    }
+    match fs::remove_dir_all(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+///
+/// Links the shared folders of the instance to the ones in `shared_dir`. Folders that already exist in the instance are left alone.
+///
+pub fn link_shared_folders(instance_dir: &Path, shared_dir: &Path) -> LauncherResult<()> {
+    for folder in SHARED_INSTANCE_FOLDERS {
+        let link = instance_dir.join(folder);
+        if link.symlink_metadata().is_ok() {
+            continue;
+        }
+        let target = shared_dir.join(folder);
+        fs::create_dir_all(&target)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, &link)?;
+        // Needs developer mode or admin rights on windows
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(&target, &link)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::launcher_rewrite::profiles::instance_dir::{first_unused_name, instance_dir_base_name, is_named_after};
+
+    #[test]
+    fn sanitizes_profile_names() {
+        assert_eq!(instance_dir_base_name("My Profile 1.20"), "My Profile 1.20");
+        assert_eq!(instance_dir_base_name("Fabric: Sodium/Iris?"), "Fabric_ Sodium_Iris_");
+        assert_eq!(instance_dir_base_name("  ..hidden.  "), "hidden");
+        assert_eq!(instance_dir_base_name(" ... "), "instance");
+        assert_eq!(instance_dir_base_name(""), "instance");
+    }
+
+    #[test]
+    fn matches_numbered_folders() {
+        assert!(is_named_after("a", "a"));
+        assert!(is_named_after("a-2", "a"));
+        assert!(is_named_after("a-12", "a"));
+        assert!(is_named_after("a-2", "a-2"));
+        assert!(is_named_after("a-2-3", "a-2"));
+        assert!(is_named_after("a_b", "a/b"));
+        assert!(!is_named_after("a-", "a"));
+        assert!(!is_named_after("a-b", "a"));
+        assert!(!is_named_after("a-2x", "a"));
+        assert!(!is_named_after("ab", "a"));
+        assert!(!is_named_after("a", "a-2"));
+        assert!(!is_named_after("b-2", "a"));
+    }
+
+    #[test]
+    fn picks_the_first_unused_name() {
+        assert_eq!(first_unused_name("a", |_| false), "a");
+        assert_eq!(first_unused_name("a", |name| name == "a"), "a-2");
+        assert_eq!(first_unused_name("a", |name| ["a", "a-2", "a-4"].contains(&name)), "a-3");
+        assert_eq!(first_unused_name("a?", |name| name == "a_"), "a_-2");
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::sync::{LazyLock, RwLock};
 
 use base64::Engine;
@@ -11,11 +12,14 @@ use serde::de::{Error, Visitor};
 use crate::launcher_rewrite::authentication::LOGGED_IN_ACCOUNT_DATA;
 use crate::launcher_rewrite::manifest::GameVersionManifest;
 use crate::launcher_rewrite::mod_loader_version_manifest::{FABRIC_MANIFEST, FORGE_MANIFEST, ModLoaderVersionManifest, NEO_FORGE_MANIFEST, QUILT_MANIFEST};
-use crate::launcher_rewrite::path_handler::{LAUNCHER_CFG_PATH, TOKENS_FILE_PATH};
+use crate::launcher_rewrite::path_handler::{get_instance_dir, LAUNCHER_CFG_PATH, TOKENS_FILE_PATH};
 use crate::launcher_rewrite::profiles::icon::LauncherProfileIcon;
 use crate::launcher_rewrite::util::config_file::{load_from_file, save_to_file};
+use crate::launcher_rewrite::util::paths::{expand_path, DEFAULT_MC_DIRECTORY};
 
 pub mod icon;
+pub mod instance_dir;
+pub mod play_history;
 
 pub static PROFILES: LazyLock<RwLock<LauncherProfiles>> = LazyLock::new(|| RwLock::new(init_launcher_profiles()));
 
@@ -54,6 +58,15 @@ impl LauncherProfiles {
         self.je_client_profiles.iter().find(|profile| profile.id() == id)
     }
 
+    pub fn remove_profile(&mut self, id: u128) -> Option<LauncherProfile> {
+        let index = self.je_client_profiles.iter().position(|profile| profile.id() == id)?;
+        let removed = self.je_client_profiles.remove(index);
+        if self.settings.selected_profile_id == id {
+            self.settings.selected_profile_id = self.je_client_profiles.first().map(LauncherProfile::id).unwrap_or_default();
+        }
+        Some(removed)
+    }
+
     pub fn settings(&self) -> LauncherPersistentState {
         self.settings
     }
@@ -76,7 +89,7 @@ impl Default for LauncherProfiles {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct LauncherProfile {
     name: String,
     #[serde(rename = "id")]
@@ -97,6 +110,30 @@ pub struct LauncherProfile {
     width: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     height: Option<u32>,
+    // Uses the java runtime downloaded by the launcher when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    java_executable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quick_play: Option<QuickPlay>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    open_output_at_launch: bool,
+    // Put in front of the java command, e.g. `gamemoderun` or `prime-run`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wrapper_command: Option<String>,
+    // Shell commands run before the game starts and after it exits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pre_launch_hook: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_exit_hook: Option<String>,
+    // Runs the game in its own folder under `instances` instead of mc_directory
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    isolated_instance: bool,
+    // Name of the instance folder, managed by the launcher. Kept when isolation is turned off again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instance_dir: Option<String>,
+    // Links the shared folders of the instance to the ones in mc_directory
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    link_shared_folders: bool,
 }
 
 impl LauncherProfile {
@@ -146,6 +183,52 @@ impl LauncherProfile {
         self.height
     }
 
+    pub fn java_executable(&self) -> Option<&str> {
+        self.java_executable.as_ref().map(|s| s.as_str())
+    }
+
+    pub fn quick_play(&self) -> Option<&QuickPlay> {
+        self.quick_play.as_ref()
+    }
+
+    pub fn open_output_at_launch(&self) -> bool {
+        self.open_output_at_launch
+    }
+
+    pub fn wrapper_command(&self) -> Option<&str> {
+        self.wrapper_command.as_deref()
+    }
+
+    pub fn pre_launch_hook(&self) -> Option<&str> {
+        self.pre_launch_hook.as_deref()
+    }
+
+    pub fn post_exit_hook(&self) -> Option<&str> {
+        self.post_exit_hook.as_deref()
+    }
+
+    pub fn isolated_instance(&self) -> bool {
+        self.isolated_instance
+    }
+
+    pub fn instance_dir(&self) -> Option<&str> {
+        self.instance_dir.as_deref()
+    }
+
+    pub fn link_shared_folders(&self) -> bool {
+        self.link_shared_folders
+    }
+
+    ///
+    /// The directory the game runs in, which is the instance folder for isolated instances
+    ///
+    pub fn game_dir(&self) -> PathBuf {
+        match (self.isolated_instance, &self.instance_dir) {
+            (true, Some(instance_dir)) => get_instance_dir(instance_dir),
+            _ => expand_path(&self.mc_directory),
+        }
+    }
+
     pub fn id(&self) -> u128 {
         self.uuid
     }
@@ -190,6 +273,42 @@ impl LauncherProfile {
         self.height = height;
     }
 
+    pub fn set_java_executable(&mut self, java_executable: Option<String>) {
+        self.java_executable = java_executable;
+    }
+
+    pub fn set_quick_play(&mut self, quick_play: Option<QuickPlay>) {
+        self.quick_play = quick_play;
+    }
+
+    pub fn set_open_output_at_launch(&mut self, open_output_at_launch: bool) {
+        self.open_output_at_launch = open_output_at_launch;
+    }
+
+    pub fn set_wrapper_command(&mut self, wrapper_command: Option<String>) {
+        self.wrapper_command = wrapper_command;
+    }
+
+    pub fn set_pre_launch_hook(&mut self, pre_launch_hook: Option<String>) {
+        self.pre_launch_hook = pre_launch_hook;
+    }
+
+    pub fn set_post_exit_hook(&mut self, post_exit_hook: Option<String>) {
+        self.post_exit_hook = post_exit_hook;
+    }
+
+    pub fn set_isolated_instance(&mut self, isolated_instance: bool) {
+        self.isolated_instance = isolated_instance;
+    }
+
+    pub fn set_instance_dir(&mut self, instance_dir: Option<String>) {
+        self.instance_dir = instance_dir;
+    }
+
+    pub fn set_link_shared_folders(&mut self, link_shared_folders: bool) {
+        self.link_shared_folders = link_shared_folders;
+    }
+
     pub fn mod_loader_version(&self) -> &str {
         &self.mod_loader_version
     }
@@ -207,16 +326,68 @@ impl Default for LauncherProfile {
             mod_loader: Default::default(),
             mod_loader_version: String::new(),
             version_name: "latest-release".to_string(),
-            mc_directory: "%appdata%/.minecraft/".to_string(), // TODO default path is OS dependent
+            mc_directory: DEFAULT_MC_DIRECTORY.to_string(),
             icon: Default::default(),
             additional_args: None,
             memory: memory_default(),
             width: None,
             height: None,
+            java_executable: None,
+            quick_play: None,
+            open_output_at_launch: false,
+            wrapper_command: None,
+            pre_launch_hook: None,
+            post_exit_hook: None,
+            isolated_instance: false,
+            instance_dir: None,
+            link_shared_folders: false,
         }
     }
 }
 
+///
+/// Where the game should go right after starting instead of the title screen
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "target", rename_all = "snake_case")]
+pub enum QuickPlay {
+    // Name of the world's folder in `saves`
+    Singleplayer(String),
+    // `host:port` or just `host`
+    Multiplayer(String),
+    // Realm id
+    Realms(String),
+}
+
+impl QuickPlay {
+    pub fn target(&self) -> &str {
+        match self {
+            QuickPlay::Singleplayer(target) => target,
+            QuickPlay::Multiplayer(target) => target,
+            QuickPlay::Realms(target) => target,
+        }
+    }
+
+    pub fn with_target(&self, target: String) -> Self {
+        match self {
+            QuickPlay::Singleplayer(_) => QuickPlay::Singleplayer(target),
+            QuickPlay::Multiplayer(_) => QuickPlay::Multiplayer(target),
+            QuickPlay::Realms(_) => QuickPlay::Realms(target),
+        }
+    }
+}
+
+impl Display for QuickPlay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            QuickPlay::Singleplayer(_) => "Singleplayer World",
+            QuickPlay::Multiplayer(_) => "Multiplayer Server",
+            QuickPlay::Realms(_) => "Realm",
+        };
+        write!(f, "{}", str)
+    }
+}
+
 pub fn convert_width_height(width: Option<u32>, height: Option<u32>) -> Option<(u32, u32)> {
     Some((width?, height?))
 }
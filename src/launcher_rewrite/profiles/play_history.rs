@@ -0,0 +1,111 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, RwLock};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::launcher_rewrite::path_handler::PLAY_HISTORY_FILE_PATH;
+use crate::launcher_rewrite::util::config_file::{load_from_file, save_to_file};
+
+pub static PLAY_HISTORY: LazyLock<RwLock<PlayHistory>> = LazyLock::new(|| RwLock::new(load_play_history()));
+
+// Older launches are dropped once a profile has this many
+const MAX_LAUNCH_HISTORY: usize = 20;
+
+fn load_play_history() -> PlayHistory {
+    load_from_file(PLAY_HISTORY_FILE_PATH.as_path(), true)
+}
+
+pub fn save_play_history() {
+    save_to_file(&*PLAY_HISTORY.read().unwrap(), PLAY_HISTORY_FILE_PATH.as_path(), true);
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PlayHistory {
+    // Keyed by profile id
+    profiles: HashMap<u128, ProfilePlayStats>,
+}
+
+impl PlayHistory {
+    pub fn stats(&self, profile_id: u128) -> Option<&ProfilePlayStats> {
+        self.profiles.get(&profile_id)
+    }
+
+    pub fn record_launch(&mut self, profile_id: u128, record: LaunchRecord) {
+        let stats = self.profiles.entry(profile_id).or_default();
+        stats.last_played = Some(stats.last_played.map_or(record.started, |last| last.max(record.started)));
+        stats.total_playtime_secs += record.duration_secs;
+        stats.history.push_front(record);
+        stats.history.truncate(MAX_LAUNCH_HISTORY);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ProfilePlayStats {
+    last_played: Option<DateTime<Utc>>,
+    total_playtime_secs: u64,
+    // Newest launch first
+    history: VecDeque<LaunchRecord>,
+}
+
+impl ProfilePlayStats {
+    pub fn last_played(&self) -> Option<DateTime<Utc>> {
+        self.last_played
+    }
+
+    pub fn total_playtime_secs(&self) -> u64 {
+        self.total_playtime_secs
+    }
+
+    pub fn history(&self) -> &VecDeque<LaunchRecord> {
+        &self.history
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LaunchRecord {
+    started: DateTime<Utc>,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    loader_version: Option<String>,
+    // None when the game was killed or the launcher couldn't get its exit status
+    exit_code: Option<i32>,
+    duration_secs: u64,
+}
+
+impl LaunchRecord {
+    pub fn new(started: DateTime<Utc>, version: String, loader_version: Option<String>, exit_code: Option<i32>, duration_secs: u64) -> Self {
+        Self { started, version, loader_version, exit_code, duration_secs }
+    }
+
+    pub fn started(&self) -> DateTime<Utc> {
+        self.started
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn loader_version(&self) -> Option<&str> {
+        self.loader_version.as_deref()
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn duration_secs(&self) -> u64 {
+        self.duration_secs
+    }
+}
+
+///
+/// Formats a playtime like `3h 12m`, or `12m` when it's under an hour
+///
+pub fn format_playtime(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
@@ -1,5 +1,6 @@
 use iced::widget::markdown::Url;
 use serde::Deserialize;
+use crate::launcher_rewrite::error::LauncherResult;
 use crate::launcher_rewrite::installer::{ACCEPT_HEADER_NAME, APPLICATION_JSON, DEFAULT_DOWNLOADER_CLIENT};
 use crate::launcher_rewrite::manifest::GAME_VERSION_MANIFEST;
 use crate::launcher_rewrite::mod_loader_version_manifest::{ModLoaderLatestVersionData, ModLoaderVersionInfo};
@@ -31,20 +32,14 @@ impl ModLoaderVersionInfo {
     }
 }
 
-pub fn get_compatible_versions(game_version: &str) -> Vec<ModLoaderVersionInfo> {
+pub fn get_compatible_versions(game_version: &str) -> LauncherResult<Vec<ModLoaderVersionInfo>> {
     // Sanitize just in case
     // Can probably be commented out
     let game_version = GAME_VERSION_MANIFEST.sanitize_version_name(game_version, ModLoader::Quilt);
     let url = format!("{}{}", QUILT_VERSIONS_URL, game_version);
-    if let Ok(response_json) = DEFAULT_DOWNLOADER_CLIENT.get(url).header(ACCEPT_HEADER_NAME, APPLICATION_JSON).send() {
-        // println!("Sent Request! Response: {:?}", response_json.text());
-        if let Ok(deserialized_vec) = serde_json::from_reader::<_, QuiltCompatibleVersionsResponse>(response_json) {
-            // println!("Deserialized: {:?}", deserialized_vec);
-            let converted = deserialized_vec.into_iter().map(|vi| ModLoaderVersionInfo::from_deserialized_quilt(vi, game_version)).collect();
-            return converted
-        }
-    }
-    return Vec::new()
+    let response_json = DEFAULT_DOWNLOADER_CLIENT.get(url).header(ACCEPT_HEADER_NAME, APPLICATION_JSON).send()?;
+    let deserialized_vec = serde_json::from_reader::<_, QuiltCompatibleVersionsResponse>(response_json)?;
+    Ok(deserialized_vec.into_iter().map(|vi| ModLoaderVersionInfo::from_deserialized_quilt(vi, game_version)).collect())
 }
 
 pub fn get_latest_supported_game_version() -> ModLoaderLatestVersionData {
@@ -1 +1,3 @@
-pub const GAME_VERSION_MANIFEST_URL: &'static str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
\ No newline at end of file
+pub const GAME_VERSION_MANIFEST_URL: &'static str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+pub const JAVA_RUNTIME_MANIFEST_URL: &'static str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+pub const MAVEN_CENTRAL_URL: &'static str = "https://repo1.maven.org/maven2/";
\ No newline at end of file
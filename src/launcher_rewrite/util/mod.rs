@@ -1,3 +1,20 @@
 pub mod hash;
 pub mod config_file;
+pub mod paths;
+pub mod shell_args;
 
+use std::fs;
+use std::path::Path;
+use crate::launcher_rewrite::error::LauncherResult;
+
+#[cfg(unix)]
+pub fn set_executable(path: &Path) -> LauncherResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn set_executable(_path: &Path) -> LauncherResult<()> {
+    Ok(())
+}
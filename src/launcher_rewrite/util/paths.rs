@@ -0,0 +1,108 @@
+use std::env;
+use std::path::PathBuf;
+use crate::launcher_rewrite::path_handler::LAUNCHER_DIR;
+
+// Where the official launcher puts the game directory, written unexpanded so that way profiles stay portable between machines
+#[cfg(target_os = "windows")]
+pub const DEFAULT_MC_DIRECTORY: &str = "%appdata%/.minecraft";
+#[cfg(target_os = "macos")]
+pub const DEFAULT_MC_DIRECTORY: &str = "~/Library/Application Support/minecraft";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const DEFAULT_MC_DIRECTORY: &str = "~/.minecraft";
+
+// Refers to the launcher's own directory in paths
+const LAUNCHER_DIR_VARIABLE: &str = "LAUNCHER_DIR";
+
+pub fn home_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let home = env::var_os("USERPROFILE");
+    #[cfg(not(target_os = "windows"))]
+    let home = env::var_os("HOME");
+    home.map(PathBuf::from)
+}
+
+///
+/// Expands a leading `~`, `%VAR%`, `$VAR` and `${VAR}` in a path entered by the user. Variables that aren't set are left as they are.
+///
+/// `HOME`, `APPDATA` and `LAUNCHER_DIR` work on every platform, so that way `%appdata%/.minecraft` from the official launcher ends up in the home directory on linux.
+///
+pub fn expand_path(path: &str) -> PathBuf {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+
+    if let Some(after_tilde) = path.strip_prefix('~') {
+        if after_tilde.is_empty() || after_tilde.starts_with(['/', '\\']) {
+            if let Some(home) = home_dir() {
+                expanded.push_str(&home.to_string_lossy());
+                rest = after_tilde;
+            }
+        }
+    }
+
+    while let Some(start) = rest.find(['%', '$']) {
+        expanded.push_str(&rest[..start]);
+        let (name, remaining) = match &rest[start..] {
+            s if s.starts_with('%') => match s[1..].find('%') {
+                Some(end) => (&s[1..end + 1], &s[end + 2..]),
+                None => ("", s),
+            },
+            s if s.starts_with("${") => match s.find('}') {
+                Some(end) => (&s[2..end], &s[end + 1..]),
+                None => ("", s),
+            },
+            s => {
+                let end = s[1..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).map_or(s.len(), |end| end + 1);
+                (&s[1..end], &s[end..])
+            }
+        };
+        match lookup_variable(name) {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = remaining;
+            }
+            None => {
+                // Not a variable, keep the sign and continue after it
+                expanded.push_str(&rest[start..start + 1]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    PathBuf::from(expanded)
+}
+
+fn lookup_variable(name: &str) -> Option<String> {
+    // Looking these up would panic
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return None;
+    }
+    if name == LAUNCHER_DIR_VARIABLE {
+        return Some(LAUNCHER_DIR.to_string_lossy().into_owned());
+    }
+    // Windows variables aren't case sensitive, and `%appdata%` is usually written in lowercase
+    if let Some(value) = env::var_os(name).or_else(|| env::var_os(name.to_uppercase())) {
+        return Some(value.to_string_lossy().into_owned());
+    }
+    match name.to_uppercase().as_str() {
+        "HOME" | "USERPROFILE" => home_dir(),
+        #[cfg(not(target_os = "windows"))]
+        "APPDATA" => home_dir(),
+        _ => None,
+    }.map(|path| path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::launcher_rewrite::path_handler::LAUNCHER_DIR;
+    use crate::launcher_rewrite::util::paths::{expand_path, home_dir};
+
+    #[test]
+    fn expands_variables() {
+        let home = home_dir().unwrap();
+        assert_eq!(expand_path("~/.minecraft"), PathBuf::from(format!("{}/.minecraft", home.display())));
+        assert_eq!(expand_path("${LAUNCHER_DIR}/instances"), PathBuf::from(format!("{}/instances", LAUNCHER_DIR.display())));
+        assert_eq!(expand_path("$LAUNCHER_DIR/a"), PathBuf::from(format!("{}/a", LAUNCHER_DIR.display())));
+        assert_eq!(expand_path("50%/$/${NOT_A_SET_VARIABLE_1234}"), PathBuf::from("50%/$/${NOT_A_SET_VARIABLE_1234}"));
+    }
+}
@@ -0,0 +1,70 @@
+///
+/// Splits a command line into separate arguments the way a shell would.
+/// Whitespace separates arguments unless it's inside single or double quotes or escaped with a backslash.
+/// A backslash only escapes quotes, whitespace and other backslashes, so that way windows paths can be used as is.
+/// Returns the reason if `input` is malformed.
+///
+pub fn split_shell_args(input: &str) -> Result<Vec<String>, &'static str> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Needed so that way `""` results in an empty argument
+    let mut in_arg = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Missing closing single quote"),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"') | Some('\\')) => current.push(chars.next().unwrap()),
+                        Some(c) => current.push(c),
+                        None => return Err("Missing closing double quote"),
+                    }
+                }
+            }
+            '\\' if chars.peek().is_some_and(|next| *next == '"' || *next == '\'' || *next == '\\' || next.is_whitespace()) => {
+                in_arg = true;
+                current.push(chars.next().unwrap());
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::launcher_rewrite::util::shell_args::split_shell_args;
+
+    #[test]
+    fn split_shell_args_test() {
+        assert_eq!(split_shell_args(r#"-XX:+UseG1GC  "-Dfoo=bar baz" '-Dquote="x"' C:\path\to\agent.jar a\ b """#).unwrap(), vec!["-XX:+UseG1GC", "-Dfoo=bar baz", "-Dquote=\"x\"", r"C:\path\to\agent.jar", "a b", ""]);
+        assert!(split_shell_args("-Dfoo=\"bar").is_err());
+        assert!(split_shell_args("'-Dfoo=bar").is_err());
+    }
+
+}
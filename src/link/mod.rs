@@ -1,5 +1,4 @@
 use std::{fs, io};
-use std::os::windows;
 use std::path::Path;
 use chrono::{TimeZone, Utc};
 
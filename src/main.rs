@@ -4,11 +4,9 @@
 use crate::launcher_rewrite::authentication::save_account_data;
 use crate::launcher_rewrite::installed_versions::save_installed_versions;
 use crate::launcher_rewrite::profiles::save_launcher_profiles;
-use crate::launcher_rewrite::GAME_INSTANCE_COUNT;
+use crate::launcher_rewrite::profiles::play_history::save_play_history;
+use crate::launcher_rewrite::instances::RUNNING_INSTANCES;
 use std::str::FromStr;
-use std::sync::atomic::Ordering;
-use std::thread::sleep;
-use std::time::Duration;
 use util::StripCanonicalization;
 
 pub mod cli;
@@ -28,13 +26,9 @@ fn main() {
     save_installed_versions();
     save_account_data();
     save_launcher_profiles();
+    save_play_history();
 
-    loop {
-        if GAME_INSTANCE_COUNT.load(Ordering::SeqCst) == 0 {
-            break;
-        }
-        // Sleep just to let the os run other tasks
-        sleep(Duration::from_millis(1000));
-    }
+    // Don't exit until all game instances were closed by the user
+    RUNNING_INSTANCES.wait_for_all();
     println!("Launcher Exiting!");
 }